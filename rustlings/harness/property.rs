@@ -0,0 +1,331 @@
+//! Property-based testing for the tests of exercises, appended to test-mode
+//! exercises by rustlings. `check` runs a property against random inputs and
+//! shrinks the first failing input down to a minimal one.
+
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CASES: u64 = 100;
+const MAX_SHRINKS: u32 = 1000;
+const SEED_VAR: &str = "RUSTLINGS_SEED";
+
+/// A small pseudo-random generator (SplitMix64), seeded for reproduction
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`, or 0 if the bound is 0
+    pub fn below(&mut self, bound: u64) -> u64 {
+        if bound == 0 {
+            0
+        } else {
+            self.next_u64() % bound
+        }
+    }
+}
+
+/// Types that random inputs can be generated for, and shrunk when they fail a property
+pub trait Arbitrary: Clone + Debug {
+    /// A random value, mostly a small one when `size` is small
+    fn arbitrary(rng: &mut Rng, size: u64) -> Self;
+
+    /// Simpler values to try instead of this one, simplest first
+    fn shrink(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+macro_rules! integers {
+    ($($t:ty),*) => {$(
+        impl Arbitrary for $t {
+            fn arbitrary(rng: &mut Rng, size: u64) -> Self {
+                match rng.below(10) {
+                    0 => <$t>::MIN,
+                    1 => <$t>::MAX,
+                    2 | 3 => rng.next_u64() as $t,
+                    _ => {
+                        let small = rng.below(size + 1) as $t;
+                        // Negative half of the time, for signed types
+                        match small.checked_neg() {
+                            Some(negative) if rng.below(2) == 0 => negative,
+                            _ => small,
+                        }
+                    }
+                }
+            }
+
+            fn shrink(&self) -> Vec<Self> {
+                let value = *self;
+                if value == 0 {
+                    return Vec::new();
+                }
+                let mut candidates = vec![0];
+                if let Some(negated) = value.checked_neg().filter(|&negated| negated > value) {
+                    candidates.push(negated);
+                }
+                // Halving the distance to zero, getting closer to the value each time
+                let mut distance = value / 2;
+                while distance != 0 {
+                    candidates.push(value - distance);
+                    distance /= 2;
+                }
+                candidates
+            }
+        }
+    )*};
+}
+
+integers!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl Arbitrary for bool {
+    fn arbitrary(rng: &mut Rng, _size: u64) -> Self {
+        rng.below(2) == 1
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        if *self {
+            vec![false]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl Arbitrary for char {
+    fn arbitrary(rng: &mut Rng, _size: u64) -> Self {
+        if rng.below(10) == 0 {
+            char::from_u32(rng.below(0x11_0000) as u32).unwrap_or('?')
+        } else {
+            // Printable ASCII
+            char::from(b' ' + rng.below(95) as u8)
+        }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        if *self == 'a' {
+            Vec::new()
+        } else {
+            vec!['a']
+        }
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Vec<T> {
+    fn arbitrary(rng: &mut Rng, size: u64) -> Self {
+        let len = rng.below(size + 1);
+        (0..len).map(|_| T::arbitrary(rng, size)).collect()
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let mut candidates = vec![Vec::new()];
+        if self.len() > 1 {
+            let (first, second) = self.split_at(self.len() / 2);
+            candidates.push(first.to_vec());
+            candidates.push(second.to_vec());
+        }
+        for i in 0..self.len() {
+            let mut removed = self.clone();
+            removed.remove(i);
+            candidates.push(removed);
+        }
+        for (i, element) in self.iter().enumerate() {
+            for simpler in element.shrink() {
+                let mut shrunk = self.clone();
+                shrunk[i] = simpler;
+                candidates.push(shrunk);
+            }
+        }
+        candidates
+    }
+}
+
+impl Arbitrary for String {
+    fn arbitrary(rng: &mut Rng, size: u64) -> Self {
+        Vec::<char>::arbitrary(rng, size).into_iter().collect()
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let chars: Vec<char> = self.chars().collect();
+        chars
+            .shrink()
+            .into_iter()
+            .map(|chars| chars.into_iter().collect())
+            .collect()
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn arbitrary(rng: &mut Rng, size: u64) -> Self {
+        if rng.below(4) == 0 {
+            None
+        } else {
+            Some(T::arbitrary(rng, size))
+        }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        match self {
+            None => Vec::new(),
+            Some(value) => {
+                let mut candidates = vec![None];
+                candidates.extend(value.shrink().into_iter().map(Some));
+                candidates
+            }
+        }
+    }
+}
+
+macro_rules! tuples {
+    ($(($($t:ident $i:tt),+)),*) => {$(
+        impl<$($t: Arbitrary),+> Arbitrary for ($($t,)+) {
+            fn arbitrary(rng: &mut Rng, size: u64) -> Self {
+                ($($t::arbitrary(rng, size),)+)
+            }
+
+            // Shrink one element at a time, keeping the others
+            fn shrink(&self) -> Vec<Self> {
+                let mut candidates = Vec::new();
+                $(
+                    for simpler in self.$i.shrink() {
+                        let mut shrunk = self.clone();
+                        shrunk.$i = simpler;
+                        candidates.push(shrunk);
+                    }
+                )+
+                candidates
+            }
+        }
+    )*};
+}
+
+tuples!((A 0, B 1), (A 0, B 1, C 2), (A 0, B 1, C 2, D 3));
+
+/// What a property returns: whether it holds, or nothing when it asserts instead
+pub trait Outcome {
+    fn holds(self) -> bool;
+}
+
+impl Outcome for bool {
+    fn holds(self) -> bool {
+        self
+    }
+}
+
+impl Outcome for () {
+    fn holds(self) -> bool {
+        true
+    }
+}
+
+impl<E> Outcome for Result<(), E> {
+    fn holds(self) -> bool {
+        self.is_ok()
+    }
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+static QUIET_HOOK: Once = Once::new();
+
+// Whether the property holds for the input. Panics count as failures, and
+// their messages aren't printed, as many inputs are tried while shrinking.
+fn passes<T, R, F>(property: &F, input: &T) -> bool
+where
+    T: Arbitrary,
+    R: Outcome,
+    F: Fn(T) -> R,
+{
+    QUIET_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(Cell::get) {
+                previous(info);
+            }
+        }));
+    });
+    QUIET.with(|quiet| quiet.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| property(input.clone()).holds()));
+    QUIET.with(|quiet| quiet.set(false));
+    result.unwrap_or(false)
+}
+
+/// Shrink an input the property fails for to a simpler one it still fails for,
+/// returning it with the number of times it was shrunk
+pub fn shrink<T, R, F>(property: &F, mut input: T) -> (T, u32)
+where
+    T: Arbitrary,
+    R: Outcome,
+    F: Fn(T) -> R,
+{
+    let mut shrinks = 0;
+    'shrinking: while shrinks < MAX_SHRINKS {
+        for candidate in input.shrink() {
+            if !passes(property, &candidate) {
+                input = candidate;
+                shrinks += 1;
+                continue 'shrinking;
+            }
+        }
+        break;
+    }
+    (input, shrinks)
+}
+
+fn seed() -> u64 {
+    match std::env::var(SEED_VAR)
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+    {
+        Some(seed) => seed,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64),
+    }
+}
+
+/// Check that the property holds for random inputs, like `check(|x: i32| x + 0 == x)`.
+/// Several inputs are passed as a tuple. When the property fails, or panics, the
+/// input is shrunk to a minimal failing one, and the test fails with the seed that
+/// reproduces it when set in the `RUSTLINGS_SEED` environment variable.
+#[track_caller]
+pub fn check<T, R, F>(property: F)
+where
+    T: Arbitrary,
+    R: Outcome,
+    F: Fn(T) -> R,
+{
+    let seed = seed();
+    let mut rng = Rng::new(seed);
+    for case in 0..CASES {
+        let input = T::arbitrary(&mut rng, case);
+        if passes(&property, &input) {
+            continue;
+        }
+        let (minimal, shrinks) = shrink(&property, input.clone());
+        // Run the minimal input again, to show the message of a failed assert
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| property(minimal.clone()).holds()));
+        panic!(
+            "The property doesn't hold for {minimal:?}, shrunk {shrinks} times from {input:?} \
+             after {case} passing cases.\nRerun with {SEED_VAR}={seed} to reproduce it."
+        );
+    }
+}
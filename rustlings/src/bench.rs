@@ -0,0 +1,146 @@
+use crate::exercise::{Benchmark, Exercise};
+
+// The line the timing harness prints its result on, as `<total nanos>/<iterations>`
+const MARKER: &str = "rustlings-bench-nanos: ";
+
+/// How long all the timed iterations of a benchmark took. Kept whole rather
+/// than divided, so that iterations under a nanosecond aren't rounded down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timing {
+    pub total_nanos: u128,
+    pub iterations: u32,
+}
+
+impl Timing {
+    /// The average nanoseconds per iteration
+    pub fn nanos(&self) -> f64 {
+        self.total_nanos as f64 / f64::from(self.iterations.max(1))
+    }
+
+    /// Whether an iteration took longer than the given nanoseconds on average
+    pub fn exceeds(&self, max_nanos: u64) -> bool {
+        self.total_nanos > u128::from(max_nanos) * u128::from(self.iterations.max(1))
+    }
+}
+
+/// The test appended to bench exercises, timing the call of the benchmark.
+/// It runs alongside the tests of the exercise, which check that the
+/// learner's code is still correct.
+pub fn harness(bench: &Benchmark) -> String {
+    format!(
+        r#"
+#[cfg(test)]
+mod rustlings_bench {{
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn rustlings_bench() {{
+        let iterations: u32 = {iterations};
+        // Warm up the caches before timing
+        std::hint::black_box({call});
+        let start = std::time::Instant::now();
+        for _ in 0..iterations {{
+            std::hint::black_box({call});
+        }}
+        println!("{MARKER}{{}}/{{}}", start.elapsed().as_nanos(), iterations);
+    }}
+}}
+"#,
+        iterations = bench.iterations,
+        call = bench.call
+    )
+}
+
+/// The timing printed by the harness
+pub fn timing(stdout: &str) -> Option<Timing> {
+    let timing = stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(MARKER))?;
+    let (total_nanos, iterations) = timing.trim().split_once('/')?;
+    Some(Timing {
+        total_nanos: total_nanos.parse().ok()?,
+        iterations: iterations.parse().ok()?,
+    })
+}
+
+/// Time the reference solution of the exercise, if there is one
+pub fn reference_timing(exercise: &Exercise) -> Option<Timing> {
+    let solution_path = exercise.solution_path();
+    if !solution_path.exists() {
+        return None;
+    }
+    let reference = Exercise {
+        path: solution_path,
+        ..exercise.clone()
+    };
+    let output = reference.compile().ok()?.run().ok()?;
+    timing(&output.stdout)
+}
+
+/// Format nanoseconds like `0.42 ns`, `850 ns`, `12.3 µs` or `4.56 ms`
+pub fn format_nanos(nanos: f64) -> String {
+    if nanos < 10.0 {
+        format!("{nanos:.2} ns")
+    } else if nanos < 1e3 {
+        format!("{nanos:.0} ns")
+    } else if nanos < 1e6 {
+        format!("{:.1} µs", nanos / 1e3)
+    } else if nanos < 1e9 {
+        format!("{:.2} ms", nanos / 1e6)
+    } else {
+        format!("{:.2} s", nanos / 1e9)
+    }
+}
+
+/// How long an iteration took, compared to the reference solution if it was timed
+pub fn describe(timing: Timing, reference: Option<Timing>) -> String {
+    let nanos = timing.nanos();
+    let timing = t!("bench_timing", time = format_nanos(nanos));
+    match reference.filter(|reference| reference.total_nanos > 0) {
+        Some(reference) => {
+            let ratio = format!("{:.2}", nanos / reference.nanos());
+            format!("{timing}, {}", t!("bench_relative", ratio = ratio))
+        }
+        None => timing,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_timing() {
+        let stdout =
+            "running 2 tests\n---- rustlings_bench stdout ----\nrustlings-bench-nanos: 1234/10\n";
+        let timing = timing(stdout).unwrap();
+        assert_eq!(
+            timing,
+            Timing {
+                total_nanos: 1234,
+                iterations: 10
+            }
+        );
+        assert_eq!(timing.nanos(), 123.4);
+        assert_eq!(super::timing("test result: ok"), None);
+    }
+
+    #[test]
+    fn test_exceeds_below_a_nanosecond() {
+        let timing = Timing {
+            total_nanos: 5,
+            iterations: 10,
+        };
+        assert!(timing.exceeds(0));
+        assert!(!timing.exceeds(1));
+    }
+
+    #[test]
+    fn test_format_nanos() {
+        assert_eq!(format_nanos(0.5), "0.50 ns");
+        assert_eq!(format_nanos(850.0), "850 ns");
+        assert_eq!(format_nanos(12_345.0), "12.3 µs");
+        assert_eq!(format_nanos(4_560_000.0), "4.56 ms");
+    }
+}
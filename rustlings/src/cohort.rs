@@ -0,0 +1,298 @@
+use crate::exercise::Exercise;
+use crate::stats::{self, ExerciseStats, Stats};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+const UNSIGNED: &str = "sha256:";
+const SIGNED: &str = "hmac-sha256:";
+// How many sticking points the cohort report lists at most
+const STICKING_POINTS: usize = 10;
+
+/// The progress of a single learner as shared with their mentors
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Progress {
+    pub learner: String,
+    /// The version of rustlings that exported the progress
+    pub version: String,
+    /// When the progress was exported, in seconds since the UNIX epoch
+    pub exported: u64,
+    /// The exercises in the order of info.toml
+    pub exercises: Vec<ExerciseProgress>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExerciseProgress {
+    pub name: String,
+    pub topic: Option<String>,
+    pub path: PathBuf,
+    pub done: bool,
+    pub stats: ExerciseStats,
+    /// The learner's current version of the exercise file
+    pub solution: String,
+}
+
+/// A file bundling the progress of a learner with a digest of it,
+/// so that mentors notice bundles that were edited by hand.
+/// With a cohort key, the digest is an HMAC that only key holders can produce.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Bundle {
+    pub progress: Progress,
+    pub digest: String,
+}
+
+impl Bundle {
+    pub fn new(
+        exercises: &[Exercise],
+        learner: String,
+        version: &str,
+        key: Option<&str>,
+    ) -> Bundle {
+        let stats = Stats::load();
+        let progress = Progress {
+            learner,
+            version: version.to_string(),
+            exported: stats::now(),
+            exercises: exercises
+                .iter()
+                .map(|exercise| ExerciseProgress {
+                    name: exercise.name.clone(),
+                    topic: exercise.topic.clone(),
+                    path: exercise.path.clone(),
+                    done: exercise.looks_done(),
+                    stats: stats.get(exercise).cloned().unwrap_or_default(),
+                    solution: fs::read_to_string(&exercise.path).unwrap_or_default(),
+                })
+                .collect(),
+        };
+        let digest = digest(&progress, key);
+        Bundle { progress, digest }
+    }
+
+    pub fn load(path: &str) -> Result<Bundle, String> {
+        let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&json).map_err(|e| e.to_string())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Failed to serialize the bundle")
+    }
+
+    /// Check that the progress hasn't changed since it was exported
+    pub fn verify(&self, key: Option<&str>) -> Result<(), String> {
+        if self.digest.starts_with(SIGNED) && key.is_none() {
+            return Err(t!("bundle_needs_key"));
+        }
+        if self.digest.starts_with(UNSIGNED) && key.is_some() {
+            return Err(t!("bundle_unsigned"));
+        }
+        if self.digest != digest(&self.progress, key) {
+            return Err(t!("bundle_tampered"));
+        }
+        Ok(())
+    }
+}
+
+// The digest of the progress serialized as compact JSON,
+// which is stable as the fields are serialized in declaration order
+fn digest(progress: &Progress, key: Option<&str>) -> String {
+    let json = serde_json::to_vec(progress).expect("Failed to serialize the progress");
+    match key {
+        Some(key) => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+                .expect("HMAC accepts keys of any length");
+            mac.update(&json);
+            format!("{SIGNED}{}", hex(&mac.finalize().into_bytes()))
+        }
+        None => format!("{UNSIGNED}{}", hex(&Sha256::digest(&json))),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+// The first exercise the learner hasn't done yet
+fn working_on(progress: &Progress) -> Option<&ExerciseProgress> {
+    progress.exercises.iter().find(|e| !e.done)
+}
+
+/// The totals of an exercise over the whole cohort
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ExerciseTotals<'a> {
+    name: &'a str,
+    // The learners currently working on the exercise
+    stuck: usize,
+    compile_failures: u32,
+    test_failures: u32,
+    hints_viewed: u32,
+    completions: u64,
+    seconds: u64,
+}
+
+impl ExerciseTotals<'_> {
+    fn average_seconds(&self) -> Option<u64> {
+        self.seconds.checked_div(self.completions)
+    }
+
+    fn struggles(&self) -> u32 {
+        self.compile_failures + self.test_failures + self.hints_viewed
+    }
+}
+
+// The exercises the cohort struggles with the most: those most learners are
+// stuck on, then those with the most failures and hints
+fn sticking_points<'a>(cohort: &'a [Progress]) -> Vec<ExerciseTotals<'a>> {
+    let mut totals: Vec<ExerciseTotals> = Vec::new();
+    for progress in cohort {
+        let current = working_on(progress).map(|e| e.name.as_str());
+        for exercise in &progress.exercises {
+            let index = match totals.iter().position(|t| t.name == exercise.name) {
+                Some(index) => index,
+                None => {
+                    totals.push(ExerciseTotals {
+                        name: &exercise.name,
+                        ..Default::default()
+                    });
+                    totals.len() - 1
+                }
+            };
+            let total = &mut totals[index];
+            if current == Some(exercise.name.as_str()) {
+                total.stuck += 1;
+            }
+            total.compile_failures += exercise.stats.compile_failures;
+            total.test_failures += exercise.stats.test_failures;
+            total.hints_viewed += exercise.stats.hints_viewed;
+            if let Some(duration) = exercise.stats.duration() {
+                total.completions += 1;
+                total.seconds += duration;
+            }
+        }
+    }
+    totals.retain(|total| total.stuck > 0 || total.struggles() > 0);
+    // The sort is stable, so ties stay in the order of the exercises
+    totals.sort_by_key(|total| std::cmp::Reverse((total.stuck, total.struggles())));
+    totals.truncate(STICKING_POINTS);
+    totals
+}
+
+/// Render the progress of a cohort as a table per learner followed by the sticking points
+pub fn cohort_report(cohort: &[Progress]) -> String {
+    let mut report = String::new();
+    let _ = writeln!(
+        report,
+        "{:<24}\t{:<16}\t{:<6}\t{}",
+        t!("cohort_learner"),
+        t!("stats_completed"),
+        t!("stats_hints"),
+        t!("cohort_working_on")
+    );
+    for progress in cohort {
+        let done = progress.exercises.iter().filter(|e| e.done).count();
+        let total = progress.exercises.len();
+        let percentage = done as f32 / total.max(1) as f32 * 100.0;
+        let completed = format!("{done}/{total} ({percentage:.1} %)");
+        let hints: u32 = progress
+            .exercises
+            .iter()
+            .map(|e| e.stats.hints_viewed)
+            .sum();
+        let current = working_on(progress).map_or("-", |e| e.name.as_str());
+        let _ = writeln!(
+            report,
+            "{:<24}\t{completed:<16}\t{hints:<6}\t{current}",
+            progress.learner
+        );
+    }
+
+    let sticking_points = sticking_points(cohort);
+    if sticking_points.is_empty() {
+        return report;
+    }
+    let _ = writeln!(report, "\n{}", t!("cohort_sticking_points"));
+    let _ = writeln!(
+        report,
+        "{:<24}\t{:<6}\t{:<16}\t{:<13}\t{:<6}\t{}",
+        t!("cohort_exercise"),
+        t!("cohort_stuck"),
+        t!("stats_compile_failures"),
+        t!("stats_test_failures"),
+        t!("stats_hints"),
+        t!("cohort_average_time")
+    );
+    for total in sticking_points {
+        let average = total
+            .average_seconds()
+            .map_or_else(|| "-".to_string(), stats::format_duration);
+        let _ = writeln!(
+            report,
+            "{:<24}\t{:<6}\t{:<16}\t{:<13}\t{:<6}\t{average}",
+            total.name,
+            total.stuck,
+            total.compile_failures,
+            total.test_failures,
+            total.hints_viewed
+        );
+    }
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn progress(learner: &str, done: usize) -> Progress {
+        Progress {
+            learner: learner.to_string(),
+            version: "5.3.0".to_string(),
+            exported: 0,
+            exercises: ["intro1", "intro2", "variables1"]
+                .iter()
+                .enumerate()
+                .map(|(i, name)| ExerciseProgress {
+                    name: name.to_string(),
+                    topic: None,
+                    path: PathBuf::from(format!("exercises/{name}.rs")),
+                    done: i < done,
+                    stats: ExerciseStats {
+                        compile_failures: i as u32,
+                        ..Default::default()
+                    },
+                    solution: String::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_verify_detects_changes() {
+        let mut bundle = Bundle {
+            digest: digest(&progress("ferris", 1), Some("cohort")),
+            progress: progress("ferris", 1),
+        };
+        assert!(bundle.verify(Some("cohort")).is_ok());
+        assert!(bundle.verify(Some("other")).is_err());
+        assert!(bundle.verify(None).is_err());
+
+        bundle.progress.exercises[1].done = true;
+        assert!(bundle.verify(Some("cohort")).is_err());
+    }
+
+    #[test]
+    fn test_sticking_points() {
+        let cohort = [
+            progress("ferris", 1),
+            progress("corro", 1),
+            progress("crab", 2),
+        ];
+        let names: Vec<&str> = sticking_points(&cohort).iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["intro2", "variables1"]);
+    }
+}
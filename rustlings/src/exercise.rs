@@ -1,16 +1,28 @@
+use crate::bench;
+use crate::property;
+use crate::toolchain;
+use crate::{i18n, ui};
 use regex::Regex;
 use serde::Deserialize;
-use std::env;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{self, Display, Formatter};
-use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::fs::{self, remove_dir_all, remove_file, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
-const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
 const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
 const CONTEXT: usize = 2;
 const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
+pub const PRISTINE_DIR: &str = ".rustlings/pristine";
+pub const SOLUTIONS_DIR: &str = "solutions";
+// The edition of exercises when neither they nor info.toml declare one
+const DEFAULT_EDITION: &str = "2021";
+// How often an exercise running with a timeout is checked on
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 // Get a temporary file name that is hopefully unique
 #[inline]
@@ -23,35 +35,224 @@ fn temp_file() -> String {
     format!("./temp_{}_{thread_id}", process::id())
 }
 
+// The arguments passed to rustc and cargo to color their output,
+// unless colors are disabled in the output configuration
+fn color_args() -> [&'static str; 2] {
+    if ui::output().color {
+        ["--color", "always"]
+    } else {
+        ["--color", "never"]
+    }
+}
+
+// Get a temporary file name for the generated source of an exercise. It's next
+// to the exercise, so that its `mod` declarations and `#[path]` attributes are
+// resolved the same, and doesn't end in `.rs`, so that `watch` ignores it.
+#[inline]
+fn temp_source_file(exercise: &Path) -> PathBuf {
+    let name = Path::new(&temp_file()).file_name().unwrap().to_owned();
+    exercise.with_file_name(format!(".{}_src", name.to_string_lossy()))
+}
+
+// Get the directory the dependencies of an exercise are compiled into
+#[inline]
+fn temp_deps_dir() -> String {
+    format!("{}_deps", temp_file())
+}
+
+// Get the directory of the Cargo project unsafe exercises are checked by Miri in
+#[inline]
+fn temp_miri_dir() -> String {
+    format!("{}_miri", temp_file())
+}
+
+// The rustc arguments building unsafe exercises with AddressSanitizer when Miri
+// isn't installed. Sanitizers are unstable, so this takes a nightly toolchain.
+pub const SANITIZER_ARGS: [&str; 2] = ["-Z", "sanitizer=address"];
+
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
+    #[default]
     Compile,
     // Indicates that the exercise should be compiled as a test harness
     Test,
     // Indicates that the exercise should be linted with clippy
     Clippy,
+    // Indicates that the exercise should be tested and timed with optimizations
+    Bench,
+    // Indicates that the exercise should be rejected by the compiler
+    #[serde(rename = "compile_fail")]
+    CompileFail,
+    // Indicates that the exercise should be tested with checks for undefined behavior,
+    // under Miri if it's installed, or else built with AddressSanitizer
+    Unsafe,
+    // Indicates that the documentation examples of the exercise should be tested
+    Doctest,
 }
 
 #[derive(Deserialize)]
 pub struct ExerciseList {
     pub exercises: Vec<Exercise>,
+    #[serde(default)]
+    pub topics: Vec<Topic>,
+    // The defaults for exercises that don't declare their own
+    #[serde(default)]
+    pub min_rustc: Option<String>,
+    #[serde(default)]
+    pub edition: Option<String>,
+}
+
+impl ExerciseList {
+    // Parse info.toml and apply the track-wide defaults to its exercises
+    pub fn parse(toml_str: &str) -> Result<ExerciseList, toml::de::Error> {
+        let mut list: ExerciseList = toml::from_str(toml_str)?;
+        for exercise in &mut list.exercises {
+            if exercise.min_rustc.is_none() {
+                exercise.min_rustc = list.min_rustc.clone();
+            }
+            if exercise.edition.is_none() {
+                exercise.edition = list.edition.clone();
+            }
+        }
+        Ok(list)
+    }
+}
+
+// A group of exercises about the same part of the language
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct Topic {
+    // Name of the topic, referred to by the `topic` of its exercises
+    pub name: String,
+    // Topics whose exercises all have to be done before this topic unlocks
+    #[serde(default)]
+    pub requires: Vec<String>,
+    // The path to the README introducing the topic
+    #[serde(default)]
+    pub readme: Option<PathBuf>,
+    // The name of the quiz exercise that has to pass for the topic to be mastered
+    #[serde(default)]
+    pub quiz: Option<String>,
+}
+
+impl Topic {
+    // The README in the learner's language if it has been translated,
+    // e.g. `README.fr.md` next to `README.md`
+    pub fn localized_readme(&self) -> Option<PathBuf> {
+        let readme = self.readme.as_ref()?;
+        i18n::languages()
+            .iter()
+            .map(|lang| readme.with_extension(format!("{lang}.md")))
+            .find(|path| path.exists())
+            .or_else(|| Some(readme.clone()))
+    }
 }
 
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Clone, Debug, Default)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
+    // The identity of the exercise across versions of the exercise set, defaults to the name.
+    // Renamed exercises keep their old id so that `rustlings upgrade` can follow them.
+    #[serde(default)]
+    pub id: Option<String>,
+    // Bumped whenever the exercise changes so much that solutions have to be redone
+    #[serde(default)]
+    pub version: u32,
     // The path to the file containing the exercise's source code
     pub path: PathBuf,
+    // The SHA-256 digest of the source as shipped, so that a pristine copy of it can
+    // be kept in workspaces that weren't set up from an archive, like git clones
+    #[serde(default)]
+    pub sha256: Option<String>,
     // The mode of the exercise (Test, Compile, or Clippy)
     pub mode: Mode,
     // The hint text associated with the exercise
     pub hint: String,
+    // Translations of the hint, keyed by language
+    #[serde(default)]
+    pub localized_hints: HashMap<String, String>,
+    // Library crates the exercise depends on
+    #[serde(default)]
+    pub deps: Vec<Dependency>,
+    // The topic the exercise belongs to
+    #[serde(default)]
+    pub topic: Option<String>,
+    // Exercises that have to be done before this one unlocks
+    #[serde(default)]
+    pub requires: Vec<String>,
+    // Tests the learner doesn't see, compiled into the exercise in test mode
+    #[serde(default)]
+    pub hidden_tests: Option<PathBuf>,
+    // Modules or functions, usually the tests, that learners mustn't alter
+    #[serde(default)]
+    pub protected: Vec<String>,
+    // The oldest rustc version the exercise works with, like "1.70"
+    #[serde(default)]
+    pub min_rustc: Option<String>,
+    // The Rust edition the exercise is written for, like "2021"
+    #[serde(default)]
+    pub edition: Option<String>,
+    // The command-line arguments the exercise runs with
+    #[serde(default)]
+    pub args: Vec<String>,
+    // The text piped into the standard input of the exercise
+    #[serde(default)]
+    pub stdin: Option<String>,
+    // What bench-mode exercises are timed with
+    #[serde(default)]
+    pub bench: Option<Benchmark>,
+    // The error code compile-fail exercises have to be rejected with, like "E0382"
+    #[serde(default)]
+    pub error_code: Option<String>,
+    // The source with `{{ placeholders }}` that every learner's variant of the exercise
+    // is generated from, at `path`
+    #[serde(default)]
+    pub template: Option<PathBuf>,
+    // The values the placeholders of the template are picked from, keyed by name
+    #[serde(default)]
+    pub params: BTreeMap<String, Param>,
+}
+
+// A library crate an exercise depends on.
+// It is compiled ahead of the exercise and passed to rustc with `--extern`
+#[derive(Deserialize, Clone, Debug)]
+pub struct Dependency {
+    // The name the exercise uses to refer to the crate
+    pub name: String,
+    // The path to the root module of the crate
+    pub path: PathBuf,
+}
+
+// The timing of a bench-mode exercise
+#[derive(Deserialize, Clone, Debug)]
+pub struct Benchmark {
+    // The expression to time, like `sum_primes(std::hint::black_box(10_000))`
+    pub call: String,
+    // How many times the expression is evaluated
+    #[serde(default = "default_iterations")]
+    pub iterations: u32,
+    // The most nanoseconds a single evaluation may take on average
+    pub max_nanos: u64,
+}
+
+fn default_iterations() -> u32 {
+    100
+}
+
+// A placeholder of a templated exercise, replaced by one of the choices or by
+// a number of the inclusive range
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct Param {
+    // Like constants, names or types
+    #[serde(default)]
+    pub choices: Vec<String>,
+    #[serde(default)]
+    pub range: Option<(i64, i64)>,
 }
 
 // An enum to track of the state of an Exercise.
@@ -78,7 +279,7 @@ pub struct ContextLine {
 // The result of compiling an exercise
 pub struct CompiledExercise<'a> {
     exercise: &'a Exercise,
-    _handle: FileHandle,
+    _handle: FileHandle<'a>,
 }
 
 impl<'a> CompiledExercise<'a> {
@@ -86,6 +287,42 @@ impl<'a> CompiledExercise<'a> {
     pub fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
         self.exercise.run()
     }
+
+    // Run the compiled exercise connected to the terminal, so that
+    // learners can type into it, and return whether it succeeded
+    pub fn run_interactively(&self) -> bool {
+        Command::new(temp_file())
+            .args(&self.exercise.args)
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+
+    // Run the compiled exercise without its output, and kill it if it's still
+    // running after the timeout. Returns whether it succeeded, or `None` if
+    // it was killed.
+    pub fn run_with_timeout(&self, timeout: Duration) -> Option<bool> {
+        let mut child = self
+            .exercise
+            .command()
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to run 'run' command");
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(status) = child.try_wait().expect("Failed to wait for the exercise") {
+                return Some(status.success());
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
 }
 
 // A representation of an already executed binary
@@ -97,64 +334,122 @@ pub struct ExerciseOutput {
     pub stderr: String,
 }
 
-struct FileHandle;
+// Why a compile_fail exercise didn't fail as expected
+pub enum CompileFailError {
+    // The compiler accepted it
+    Compiled,
+    // The compiler rejected it with another error, or failed for another reason
+    WrongError(ExerciseOutput),
+}
+
+struct FileHandle<'a>(&'a Path);
 
-impl Drop for FileHandle {
+impl Drop for FileHandle<'_> {
     fn drop(&mut self) {
-        clean();
+        clean(self.0);
     }
 }
 
 impl Exercise {
-    pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
+    pub fn compile(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
         let cmd = match self.mode {
-            Mode::Compile => Command::new("rustc")
-                .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
+            Mode::Compile | Mode::CompileFail => toolchain::rustc()
+                .args([self.path.to_str().unwrap(), "-o", &temp_file()])
+                .args(["--edition", self.edition()])
+                .args(self.compile_deps()?)
+                .args(color_args())
+                .output(),
+            Mode::Test => toolchain::rustc()
+                .arg("--test")
+                .args(["--edition", self.edition()])
+                .args(self.test_source()?)
+                .args(["-o", &temp_file()])
+                .args(self.compile_deps()?)
+                .args(color_args())
                 .output(),
-            Mode::Test => Command::new("rustc")
-                .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
+            Mode::Bench => toolchain::rustc()
+                .arg("--test")
+                .args(["--edition", self.edition()])
+                .args(["-C", "opt-level=3"])
+                .args(self.test_source()?)
+                .args(["-o", &temp_file()])
+                .args(self.compile_deps()?)
+                .args(color_args())
+                .output(),
+            Mode::Unsafe if self.uses_miri() => {
+                let manifest = self.write_miri_manifest()?;
+                toolchain::cargo()
+                    .args(["miri", "test", "--no-run", "--manifest-path", &manifest])
+                    .args(color_args())
+                    .output()
+            }
+            Mode::Unsafe => toolchain::rustc()
+                .arg("--test")
+                .args(["--edition", self.edition()])
+                .args(SANITIZER_ARGS)
+                .args(self.test_source()?)
+                .args(["-o", &temp_file()])
+                .args(self.compile_deps()?)
+                .args(color_args())
                 .output(),
+            // Built as a library first, which the examples are linked against,
+            // so that errors outside of the examples are reported like in the other modes
+            Mode::Doctest => {
+                let externs = self.compile_deps()?;
+                fs::create_dir_all(temp_deps_dir())
+                    .expect("Failed to create the dependency directory");
+                toolchain::rustc()
+                    .args(["--crate-type", "lib", "--crate-name", &self.crate_name()])
+                    .args(["--edition", self.edition()])
+                    .arg(&self.path)
+                    .args(["-o", &self.library()])
+                    .args(externs)
+                    .args(color_args())
+                    .output()
+            }
             Mode::Clippy => {
                 let cargo_toml = format!(
                     r#"[package]
 name = "{}"
 version = "0.0.1"
-edition = "2021"
+edition = "{}"
 [[bin]]
 name = "{}"
 path = "{}.rs""#,
-                    self.name, self.name, self.name
+                    self.name,
+                    self.edition(),
+                    self.name,
+                    self.name
                 );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
-                } else {
+                let cargo_toml_error_msg = if ui::output().emoji {
                     "Failed to write 📎 Clippy 📎 Cargo.toml file."
+                } else {
+                    "Failed to write Clippy Cargo.toml file."
                 };
                 fs::write(CLIPPY_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
                 // To support the ability to run the clippy exercises, build
                 // an executable, in addition to running clippy. With a
                 // compilation failure, this would silently fail. But we expect
                 // clippy to reflect the same failure while compiling later.
-                Command::new("rustc")
-                    .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                    .args(RUSTC_COLOR_ARGS)
+                toolchain::rustc()
+                    .args([self.path.to_str().unwrap(), "-o", &temp_file()])
+                    .args(["--edition", self.edition()])
+                    .args(color_args())
                     .output()
                     .expect("Failed to compile!");
                 // Due to an issue with Clippy, a cargo clean is required to catch all lints.
                 // See https://github.com/rust-lang/rust-clippy/issues/2604
                 // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
                 // https://github.com/rust-lang/rust-clippy/issues/3837
-                Command::new("cargo")
-                    .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
+                toolchain::cargo()
+                    .args(["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+                    .args(color_args())
                     .output()
                     .expect("Failed to run 'cargo clean'");
-                Command::new("cargo")
-                    .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(&["--", "-D", "warnings","-D","clippy::float_cmp"])
+                toolchain::cargo()
+                    .args(["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
+                    .args(color_args())
+                    .args(["--", "-D", "warnings", "-D", "clippy::float_cmp"])
                     .output()
             }
         }
@@ -163,10 +458,10 @@ path = "{}.rs""#,
         if cmd.status.success() {
             Ok(CompiledExercise {
                 exercise: self,
-                _handle: FileHandle,
+                _handle: FileHandle(&self.path),
             })
         } else {
-            clean();
+            clean(&self.path);
             Err(ExerciseOutput {
                 stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
                 stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
@@ -174,15 +469,205 @@ path = "{}.rs""#,
         }
     }
 
-    fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        let arg = match self.mode {
-            Mode::Test => "--show-output",
-            _ => "",
+    // The arguments passing the source to compile as a test harness to rustc:
+    // the exercise itself, or a copy of it with the hidden tests and the timing
+    // harness of benchmarks appended as modules. Compiler messages still refer
+    // to the exercise file then.
+    fn test_source(&self) -> Result<Vec<String>, ExerciseOutput> {
+        let path = self.path.to_str().unwrap().to_string();
+        let mut appended = String::new();
+        if let Some(hidden_tests) = &self.hidden_tests {
+            let hidden_tests = hidden_tests
+                .canonicalize()
+                .map_err(|error| ExerciseOutput {
+                    stdout: String::new(),
+                    stderr: t!(
+                        "hidden_tests_missing",
+                        path = hidden_tests.display(),
+                        error = error
+                    ),
+                })?;
+            appended.push_str(&format!(
+                "\n#[cfg(test)]\n#[path = {:?}]\nmod rustlings_hidden_tests;\n",
+                hidden_tests.to_string_lossy()
+            ));
+        }
+        if self.mode == Mode::Test {
+            appended.push_str(&property::prelude());
+        }
+        if let (Mode::Bench, Some(benchmark)) = (self.mode, &self.bench) {
+            appended.push_str(&bench::harness(benchmark));
+        }
+        if appended.is_empty() {
+            return Ok(vec![path]);
+        }
+        let temp_source = temp_source_file(&self.path);
+        fs::read_to_string(&self.path)
+            .and_then(|source| fs::write(&temp_source, format!("{source}{appended}")))
+            .map_err(|error| ExerciseOutput {
+                stdout: String::new(),
+                stderr: t!("test_source_failed", exercise = self, error = error),
+            })?;
+        let temp_source = temp_source.to_str().unwrap().to_string();
+        // The name would otherwise be derived from the file name, which isn't a valid one
+        Ok(vec![
+            temp_source.clone(),
+            "--crate-name".to_string(),
+            self.crate_name(),
+            format!("--remap-path-prefix={temp_source}={path}"),
+        ])
+    }
+
+    // Compile the dependencies of the exercise as libraries and
+    // return the `--extern` arguments needed to link against them
+    fn compile_deps(&self) -> Result<Vec<String>, ExerciseOutput> {
+        if self.deps.is_empty() {
+            return Ok(Vec::new());
+        }
+        let deps_dir = temp_deps_dir();
+        fs::create_dir_all(&deps_dir).expect("Failed to create the dependency directory");
+
+        for dep in &self.deps {
+            let rlib = format!("{deps_dir}/lib{}.rlib", dep.name);
+            let mut command = toolchain::rustc();
+            command
+                .args(["--crate-type", "lib", "--crate-name", &dep.name])
+                .args(["--edition", self.edition()])
+                .arg(&dep.path)
+                .args(["-o", &rlib])
+                .args(color_args());
+            if self.mode == Mode::Unsafe {
+                command.args(SANITIZER_ARGS);
+            }
+            let cmd = command.output().expect("Failed to compile a dependency");
+            if !cmd.status.success() {
+                clean(&self.path);
+                return Err(ExerciseOutput {
+                    stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
+                    stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+                });
+            }
+        }
+        Ok(self.extern_args())
+    }
+
+    // The name doctests refer to the exercise by, which rustdoc derives from the file name
+    fn crate_name(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().replace('-', "_"))
+            .unwrap_or_else(|| self.name.clone())
+    }
+
+    // Where doctest exercises are compiled to, next to their dependencies
+    fn library(&self) -> String {
+        format!("{}/lib{}.rlib", temp_deps_dir(), self.crate_name())
+    }
+
+    // The arguments linking against the compiled dependencies
+    fn extern_args(&self) -> Vec<String> {
+        self.deps
+            .iter()
+            .flat_map(|dep| {
+                [
+                    "--extern".to_string(),
+                    format!("{}={}/lib{}.rlib", dep.name, temp_deps_dir(), dep.name),
+                ]
+            })
+            .collect()
+    }
+
+    // Unsafe exercises are checked by Miri if it's installed, as a Cargo
+    // project of their own. Exercises with dependencies can't be.
+    fn uses_miri(&self) -> bool {
+        self.mode == Mode::Unsafe && self.deps.is_empty() && toolchain::has_miri()
+    }
+
+    // Whether the exercise is checked for undefined behavior, which unsafe
+    // exercises can't be without Miri or a toolchain supporting AddressSanitizer
+    pub fn can_check_undefined_behavior(&self) -> bool {
+        self.mode != Mode::Unsafe || self.uses_miri() || toolchain::has_sanitizer()
+    }
+
+    // Write the Cargo project Miri tests the exercise in and return its manifest
+    fn write_miri_manifest(&self) -> Result<String, ExerciseOutput> {
+        let source = PathBuf::from(&self.test_source()?[0])
+            .canonicalize()
+            .expect("Failed to find the exercise file");
+        let cargo_toml = format!(
+            "[package]\nname = \"{}\"\nversion = \"0.0.1\"\nedition = \"{}\"\n[lib]\npath = {:?}\n",
+            self.name,
+            self.edition(),
+            source.to_string_lossy()
+        );
+        let dir = temp_miri_dir();
+        fs::create_dir_all(&dir).expect("Failed to create the Miri project");
+        let manifest = format!("{dir}/Cargo.toml");
+        fs::write(&manifest, cargo_toml).expect("Failed to write the Miri Cargo.toml");
+        Ok(manifest)
+    }
+
+    // The command running the compiled exercise
+    fn command(&self) -> Command {
+        let mut command = if self.uses_miri() {
+            let mut command = toolchain::cargo();
+            let manifest = format!("{}/Cargo.toml", temp_miri_dir());
+            command
+                .args(["miri", "test", "--manifest-path", &manifest])
+                .args(color_args())
+                .arg("--");
+            command
+        } else if self.mode == Mode::Doctest {
+            let mut command = toolchain::rustdoc();
+            command
+                .arg("--test")
+                .args(["--edition", self.edition()])
+                .arg(&self.path)
+                .args(self.extern_args())
+                .arg("--extern")
+                .arg(format!("{}={}", self.crate_name(), self.library()))
+                .args(color_args());
+            command
+        } else {
+            Command::new(temp_file())
         };
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
-            .expect("Failed to run 'run' command");
+        match self.mode {
+            Mode::Test | Mode::Unsafe => command.arg("--show-output"),
+            Mode::Doctest => &mut command,
+            // Benchmarks are timed one at a time
+            Mode::Bench => command.args(["--show-output", "--test-threads=1"]),
+            _ => command.args(&self.args),
+        };
+        command
+    }
+
+    fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
+        let mut command = self.command();
+        let cmd = match &self.stdin {
+            Some(stdin) => {
+                let mut child = command
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .expect("Failed to run 'run' command");
+                let mut pipe = child
+                    .stdin
+                    .take()
+                    .expect("The stdin of the exercise is piped");
+                // Written from another thread while the output is read, as the
+                // exercise may fill its output pipe before reading all of its input.
+                // It may not read all of it either, so failing to write is fine.
+                thread::scope(|scope| {
+                    scope.spawn(move || {
+                        let _ = pipe.write_all(stdin.as_bytes());
+                    });
+                    child.wait_with_output()
+                })
+            }
+            None => command.output(),
+        }
+        .expect("Failed to run 'run' command");
 
         let output = ExerciseOutput {
             stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
@@ -196,6 +681,14 @@ path = "{}.rs""#,
         }
     }
 
+    // The hint in the learner's language, falling back to the English one
+    pub fn localized_hint(&self) -> &str {
+        i18n::languages()
+            .iter()
+            .find_map(|lang| self.localized_hints.get(lang))
+            .unwrap_or(&self.hint)
+    }
+
     pub fn state(&self) -> State {
         let mut source_file =
             File::open(&self.path).expect("We were unable to open the exercise file!");
@@ -208,33 +701,93 @@ path = "{}.rs""#,
             s
         };
 
-        let re = Regex::new(I_AM_DONE_REGEX).unwrap();
+        source_state(&source)
+    }
+
+    // The edition the exercise is compiled with
+    pub fn edition(&self) -> &str {
+        self.edition.as_deref().unwrap_or(DEFAULT_EDITION)
+    }
 
-        if !re.is_match(&source) {
-            return State::Done;
+    // Compile an exercise that the compiler has to reject, and check that it
+    // does so with the expected error
+    pub fn compile_expecting_error(&self) -> Result<(), CompileFailError> {
+        match self.compile() {
+            Err(output) if self.has_expected_error(&output.stderr) => Ok(()),
+            Err(output) => Err(CompileFailError::WrongError(output)),
+            Ok(_) => Err(CompileFailError::Compiled),
         }
+    }
 
-        let matched_line_index = source
-            .lines()
-            .enumerate()
-            .find_map(|(i, line)| if re.is_match(line) { Some(i) } else { None })
-            .expect("This should not happen at all");
-
-        let min_line = ((matched_line_index as i32) - (CONTEXT as i32)).max(0) as usize;
-        let max_line = matched_line_index + CONTEXT;
-
-        let context = source
-            .lines()
-            .enumerate()
-            .filter(|&(i, _)| i >= min_line && i <= max_line)
-            .map(|(i, line)| ContextLine {
-                line: line.to_string(),
-                number: i + 1,
-                important: i == matched_line_index,
-            })
-            .collect();
+    // Whether the compiler reported the expected error, or any error if the
+    // exercise doesn't expect a specific one, at a location in the exercise.
+    // Failures that aren't about the code, like a missing file, don't count.
+    fn has_expected_error(&self, stderr: &str) -> bool {
+        let header = match &self.error_code {
+            Some(code) => format!("error[{code}]"),
+            None => "error".to_string(),
+        };
+        let location = format!("--> {}:", self.path.display());
+        let stderr = console::strip_ansi_codes(stderr);
+        let lines: Vec<&str> = stderr.lines().collect();
+        lines.windows(2).any(|pair| {
+            let diagnostic = pair[0].strip_prefix(&header).is_some_and(|rest| {
+                rest.starts_with(':') || (self.error_code.is_none() && rest.starts_with("[E"))
+            });
+            diagnostic && pair[1].trim_start().starts_with(&location)
+        })
+    }
+
+    pub fn id(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.name)
+    }
+
+    // The path of the reference solution, which mirrors the path of the
+    // exercise in the solutions directory, e.g. `solutions/if/if1.rs`
+    pub fn solution_path(&self) -> PathBuf {
+        let relative = self.path.strip_prefix("exercises").unwrap_or(&self.path);
+        Path::new(SOLUTIONS_DIR).join(relative)
+    }
+
+    // The source of the exercise as it was shipped, before the learner edited it.
+    // Only the copy in `.rustlings/pristine` is trusted: anything the learner
+    // can commit, like the git history, could have been rewritten.
+    pub fn pristine_source(&self) -> Option<String> {
+        fs::read_to_string(Path::new(PRISTINE_DIR).join(&self.path)).ok()
+    }
 
-        State::Pending(context)
+    // Keep a pristine copy of the exercise while it's still the shipped version,
+    // which is checked against the digest in info.toml. Workspaces set up from an
+    // archive have them from the start, other ones get them the first time
+    // rustlings runs, before the learner edits the exercises.
+    pub fn keep_pristine(&self) -> io::Result<()> {
+        if self.sha256.is_none() {
+            return Ok(());
+        }
+        let pristine = Path::new(PRISTINE_DIR).join(&self.path);
+        if self.shipped_source(&pristine).is_some() {
+            return Ok(());
+        }
+        match self.shipped_source(&self.path) {
+            Some(source) => {
+                if let Some(parent) = pristine.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(pristine, source)
+            }
+            // The copy of a version that isn't shipped anymore, like before a `git pull`,
+            // would pass the new tests off as the learner's changes
+            None if pristine.exists() => fs::remove_file(pristine),
+            None => Ok(()),
+        }
+    }
+
+    // The contents of the file if they match the digest of the shipped source in info.toml
+    pub fn shipped_source(&self, path: &Path) -> Option<Vec<u8>> {
+        let expected = self.sha256.as_ref()?.to_lowercase();
+        fs::read(path)
+            .ok()
+            .filter(|source| sha256(source) == expected)
     }
 
     // Check that the exercise looks to be solved using self.state()
@@ -248,36 +801,110 @@ path = "{}.rs""#,
     }
 }
 
+// The SHA-256 digest of the contents, in lowercase hex like `sha256sum` prints it
+fn sha256(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
 impl Display for Exercise {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "{}", self.path.to_str().unwrap())
     }
 }
 
+// Compute the state of an exercise from its source code.
+// This is used for files on disk as well as for unsaved editor buffers.
+pub fn source_state(source: &str) -> State {
+    let re = Regex::new(I_AM_DONE_REGEX).unwrap();
+
+    if !re.is_match(source) {
+        return State::Done;
+    }
+
+    let matched_line_index = source
+        .lines()
+        .enumerate()
+        .find_map(|(i, line)| if re.is_match(line) { Some(i) } else { None })
+        .expect("This should not happen at all");
+
+    let min_line = ((matched_line_index as i32) - (CONTEXT as i32)).max(0) as usize;
+    let max_line = matched_line_index + CONTEXT;
+
+    let context = source
+        .lines()
+        .enumerate()
+        .filter(|&(i, _)| i >= min_line && i <= max_line)
+        .map(|(i, line)| ContextLine {
+            line: line.to_string(),
+            number: i + 1,
+            important: i == matched_line_index,
+        })
+        .collect();
+
+    State::Pending(context)
+}
+
 #[inline]
-fn clean() {
-    let _ignored = remove_file(&temp_file());
+fn clean(exercise: &Path) {
+    let _ignored = remove_file(temp_file());
+    let _ignored = remove_file(temp_source_file(exercise));
+    let _ignored = remove_dir_all(temp_deps_dir());
+    let _ignored = remove_dir_all(temp_miri_dir());
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::path::Path;
+
+    #[test]
+    fn test_shipped_exercises_match_their_digest() {
+        let list = ExerciseList::parse(&fs::read_to_string("info.toml").unwrap()).unwrap();
+        for exercise in list.exercises.iter().filter(|e| e.template.is_none()) {
+            let source = fs::read(&exercise.path).unwrap();
+            assert_eq!(
+                exercise.sha256.as_deref(),
+                Some(sha256(&source).as_str()),
+                "the sha256 of {} in info.toml is out of date",
+                exercise.name
+            );
+        }
+    }
 
     #[test]
     fn test_clean() {
-        File::create(&temp_file()).unwrap();
+        File::create(temp_file()).unwrap();
         let exercise = Exercise {
             name: String::from("example"),
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
             hint: String::from(""),
+            ..Default::default()
         };
         let compiled = exercise.compile().unwrap();
         drop(compiled);
         assert!(!Path::new(&temp_file()).exists());
     }
 
+    #[test]
+    fn test_has_expected_error() {
+        let mut exercise = Exercise {
+            path: PathBuf::from("exercises/moved.rs"),
+            mode: Mode::CompileFail,
+            ..Default::default()
+        };
+        let moved = "error[E0382]: borrow of moved value: `v`\n --> exercises/moved.rs:4:20\n";
+        let elsewhere = "error[E0382]: borrow of moved value: `v`\n --> exercises/other.rs:4:20\n";
+        let unreadable = "error: couldn't read `exercises/moved.rs`: No such file or directory\n";
+        assert!(exercise.has_expected_error(moved));
+        assert!(!exercise.has_expected_error(elsewhere));
+        assert!(!exercise.has_expected_error(unreadable));
+
+        exercise.error_code = Some("E0382".to_string());
+        assert!(exercise.has_expected_error(moved));
+        exercise.error_code = Some("E0308".to_string());
+        assert!(!exercise.has_expected_error(moved));
+    }
+
     #[test]
     fn test_pending_state() {
         let exercise = Exercise {
@@ -285,6 +912,7 @@ mod test {
             path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
             mode: Mode::Compile,
             hint: String::new(),
+            ..Default::default()
         };
 
         let state = exercise.state();
@@ -326,6 +954,7 @@ mod test {
             path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
             mode: Mode::Compile,
             hint: String::new(),
+            ..Default::default()
         };
 
         assert_eq!(exercise.state(), State::Done);
@@ -338,6 +967,7 @@ mod test {
             path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
             mode: Mode::Test,
             hint: String::new(),
+            ..Default::default()
         };
         let out = exercise.compile().unwrap().run().unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
@@ -0,0 +1,301 @@
+use crate::exercise::{Exercise, Topic};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// The prerequisites of every exercise, resolved from the `requires`
+/// of the exercises and of their topics in info.toml.
+/// An exercise is unlocked once all of its prerequisites are done.
+pub struct ExerciseGraph<'a> {
+    exercises: &'a [Exercise],
+    topics: &'a [Topic],
+    // The indices of the exercises each exercise directly depends on
+    prerequisites: Vec<Vec<usize>>,
+}
+
+// The state of a node while searching the graph for cycles
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    InProgress,
+    Finished,
+}
+
+impl<'a> ExerciseGraph<'a> {
+    /// Resolve the prerequisites of the exercises, failing on unknown
+    /// exercises or topics and on prerequisites that form a cycle
+    pub fn new(exercises: &'a [Exercise], topics: &'a [Topic]) -> Result<Self, String> {
+        let indices: HashMap<&str, usize> = exercises
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (e.name.as_str(), i))
+            .collect();
+
+        for topic in topics {
+            if let Some(unknown) = topic
+                .requires
+                .iter()
+                .find(|required| !topics.iter().any(|t| &&t.name == required))
+            {
+                return Err(t!(
+                    "graph_unknown_topic",
+                    name = &topic.name,
+                    topic = unknown
+                ));
+            }
+        }
+
+        for topic in topics {
+            if let Some(quiz) = topic
+                .quiz
+                .as_ref()
+                .filter(|q| !indices.contains_key(q.as_str()))
+            {
+                return Err(t!(
+                    "topic_unknown_quiz",
+                    name = &topic.name,
+                    exercise = quiz
+                ));
+            }
+        }
+
+        let mut prerequisites = Vec::with_capacity(exercises.len());
+        for exercise in exercises {
+            let mut required = Vec::new();
+            for name in &exercise.requires {
+                match indices.get(name.as_str()) {
+                    Some(&index) => required.push(index),
+                    None => {
+                        return Err(t!(
+                            "graph_unknown_exercise",
+                            name = &exercise.name,
+                            exercise = name
+                        ))
+                    }
+                }
+            }
+            let topic_requires = exercise
+                .topic
+                .as_ref()
+                .and_then(|name| topics.iter().find(|t| &t.name == name))
+                .map(|topic| topic.requires.as_slice())
+                .unwrap_or_default();
+            for (index, other) in exercises.iter().enumerate() {
+                let in_required_topic = other
+                    .topic
+                    .as_ref()
+                    .is_some_and(|name| topic_requires.contains(name));
+                if in_required_topic && !required.contains(&index) {
+                    required.push(index);
+                }
+            }
+            prerequisites.push(required);
+        }
+
+        let graph = ExerciseGraph {
+            exercises,
+            topics,
+            prerequisites,
+        };
+        graph.check_cycles()?;
+        Ok(graph)
+    }
+
+    fn check_cycles(&self) -> Result<(), String> {
+        fn visit(
+            graph: &ExerciseGraph,
+            index: usize,
+            visits: &mut [Visit],
+            path: &mut Vec<usize>,
+        ) -> Result<(), String> {
+            match visits[index] {
+                Visit::Finished => return Ok(()),
+                Visit::InProgress => {
+                    let start = path.iter().position(|&i| i == index).unwrap_or(0);
+                    let cycle: Vec<&str> = path[start..]
+                        .iter()
+                        .chain(std::iter::once(&index))
+                        .map(|&i| graph.exercises[i].name.as_str())
+                        .collect();
+                    return Err(t!("graph_cycle", cycle = cycle.join(" -> ")));
+                }
+                Visit::New => {}
+            }
+            visits[index] = Visit::InProgress;
+            path.push(index);
+            for &required in &graph.prerequisites[index] {
+                visit(graph, required, visits, path)?;
+            }
+            path.pop();
+            visits[index] = Visit::Finished;
+            Ok(())
+        }
+
+        let mut visits = vec![Visit::New; self.exercises.len()];
+        for index in 0..self.exercises.len() {
+            visit(self, index, &mut visits, &mut Vec::new())?;
+        }
+        Ok(())
+    }
+
+    fn index_of(&self, exercise: &Exercise) -> Option<usize> {
+        self.exercises.iter().position(|e| e.name == exercise.name)
+    }
+
+    /// The prerequisites of the exercise that aren't done yet
+    pub fn missing_prerequisites(&self, exercise: &Exercise) -> Vec<&'a Exercise> {
+        self.index_of(exercise)
+            .map(|index| {
+                self.prerequisites[index]
+                    .iter()
+                    .map(|&required| &self.exercises[required])
+                    .filter(|required| !required.looks_done())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether all prerequisites of the exercise are done
+    pub fn is_unlocked(&self, exercise: &Exercise) -> bool {
+        self.missing_prerequisites(exercise).is_empty()
+    }
+
+    // The topics in the order their first exercise appears in info.toml,
+    // followed by topics without exercises. `None` groups exercises without a topic.
+    fn topic_order(&self) -> Vec<Option<&'a str>> {
+        let mut order: Vec<Option<&str>> = Vec::new();
+        for exercise in self.exercises {
+            let topic = exercise.topic.as_deref();
+            if !order.contains(&topic) {
+                order.push(topic);
+            }
+        }
+        for topic in self.topics {
+            if !order.contains(&Some(topic.name.as_str())) {
+                order.push(Some(topic.name.as_str()));
+            }
+        }
+        order
+    }
+
+    fn topic_requires(&self, name: &str) -> &'a [String] {
+        self.topics
+            .iter()
+            .find(|topic| topic.name == name)
+            .map(|topic| topic.requires.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Render the graph as indented text, grouped by topic
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for topic in self.topic_order() {
+            match topic {
+                Some(name) => {
+                    let requires = self.topic_requires(name);
+                    if requires.is_empty() {
+                        let _ = writeln!(text, "{name}");
+                    } else {
+                        let _ = writeln!(text, "{name} <- {}", requires.join(", "));
+                    }
+                }
+                None => {
+                    let _ = writeln!(text, "{}", t!("graph_no_topic"));
+                }
+            }
+            for exercise in self
+                .exercises
+                .iter()
+                .filter(|e| e.topic.as_deref() == topic)
+            {
+                let marker = if exercise.looks_done() {
+                    "[x]"
+                } else if self.is_unlocked(exercise) {
+                    "[ ]"
+                } else {
+                    "[-]"
+                };
+                if exercise.requires.is_empty() {
+                    let _ = writeln!(text, "  {marker} {}", exercise.name);
+                } else {
+                    let _ = writeln!(
+                        text,
+                        "  {marker} {} <- {}",
+                        exercise.name,
+                        exercise.requires.join(", ")
+                    );
+                }
+            }
+        }
+        text
+    }
+
+    /// Render the graph in the DOT language of Graphviz,
+    /// with one cluster per topic and done exercises filled in
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph rustlings {\n    rankdir=LR;\n    compound=true;\n");
+        for (cluster, topic) in self.topic_order().into_iter().enumerate() {
+            let indent = if let Some(name) = topic {
+                let _ = writeln!(dot, "    subgraph cluster_{cluster} {{");
+                let _ = writeln!(dot, "        label={name:?};");
+                "        "
+            } else {
+                "    "
+            };
+            for exercise in self
+                .exercises
+                .iter()
+                .filter(|e| e.topic.as_deref() == topic)
+            {
+                let style = if exercise.looks_done() {
+                    " [style=filled, fillcolor=palegreen]"
+                } else if !self.is_unlocked(exercise) {
+                    " [style=dashed]"
+                } else {
+                    ""
+                };
+                let _ = writeln!(dot, "{indent}{:?}{style};", exercise.name);
+            }
+            if topic.is_some() {
+                let _ = writeln!(dot, "    }}");
+            }
+        }
+
+        for exercise in self.exercises {
+            for required in &exercise.requires {
+                let _ = writeln!(dot, "    {required:?} -> {:?};", exercise.name);
+            }
+        }
+        // Topic requirements connect the clusters through their last and first exercises
+        let order = self.topic_order();
+        let cluster_of = |name: &str| order.iter().position(|t| *t == Some(name));
+        let last_of = |name: &str| {
+            self.exercises
+                .iter()
+                .rev()
+                .find(|e| e.topic.as_deref() == Some(name))
+        };
+        let first_of = |name: &str| {
+            self.exercises
+                .iter()
+                .find(|e| e.topic.as_deref() == Some(name))
+        };
+        for topic in self.topics {
+            for required in &topic.requires {
+                if let (Some(from), Some(to), Some(tail), Some(head)) = (
+                    last_of(required),
+                    first_of(&topic.name),
+                    cluster_of(required),
+                    cluster_of(&topic.name),
+                ) {
+                    let _ = writeln!(
+                        dot,
+                        "    {:?} -> {:?} [ltail=cluster_{tail}, lhead=cluster_{head}];",
+                        from.name, to.name
+                    );
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -0,0 +1,150 @@
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// The message catalogs shipped with rustlings. English is the reference
+// catalog that every other locale falls back to.
+const DEFAULT_LOCALE: &str = "en";
+const PLACEHOLDER_REGEX: &str = r"\{(\w+)\}";
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.toml")),
+    ("fr", include_str!("../locales/fr.toml")),
+];
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+// The language chosen by the learner together with its messages
+struct Locale {
+    // Lookup candidates in order of preference, e.g. ["pt_br", "pt"]
+    candidates: Vec<String>,
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Locale {
+    fn new(lang: Option<&str>) -> Locale {
+        let candidates = lang.map(candidates).unwrap_or_default();
+        let messages = candidates
+            .iter()
+            .find_map(|candidate| catalog(candidate))
+            .unwrap_or_default();
+        Locale {
+            candidates,
+            messages,
+            fallback: catalog(DEFAULT_LOCALE).unwrap_or_default(),
+        }
+    }
+}
+
+// Parse one of the shipped catalogs
+fn catalog(lang: &str) -> Option<HashMap<String, String>> {
+    CATALOGS
+        .iter()
+        .find(|(name, _)| *name == lang)
+        .map(|(_, source)| toml::from_str(source).expect("Failed to parse a message catalog"))
+}
+
+// Turn a language tag like `pt-BR` or a POSIX locale like `fr_FR.UTF-8`
+// into lookup candidates, from the most to the least specific
+fn candidates(lang: &str) -> Vec<String> {
+    let lang = lang
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+        .replace('-', "_");
+    let mut candidates = vec![lang.clone()];
+    if let Some((primary, _)) = lang.split_once('_') {
+        candidates.push(primary.to_string());
+    }
+    candidates.retain(|candidate| !candidate.is_empty());
+    candidates
+}
+
+fn locale() -> &'static Locale {
+    LOCALE.get_or_init(|| Locale::new(None))
+}
+
+/// Choose the language of every message printed from now on.
+/// Unknown languages and missing messages fall back to English.
+pub fn init(lang: Option<&str>) {
+    let _ = LOCALE.set(Locale::new(lang));
+}
+
+/// The lookup candidates for localized content such as hints,
+/// from the most to the least specific. Empty for the default language.
+pub fn languages() -> &'static [String] {
+    &locale().candidates
+}
+
+/// Look up the message with the given key and fill in its `{placeholders}`
+pub fn translate(key: &str, args: &[(&str, String)]) -> String {
+    let locale = locale();
+    let template = locale
+        .messages
+        .get(key)
+        .or_else(|| locale.fallback.get(key))
+        .map(String::as_str)
+        .unwrap_or(key);
+    fill(template, args)
+}
+
+// Replace the placeholders of the template in a single pass, so that
+// braces in the values themselves are left alone
+fn fill(template: &str, args: &[(&str, String)]) -> String {
+    let re = Regex::new(PLACEHOLDER_REGEX).unwrap();
+    re.replace_all(template, |captures: &Captures| {
+        match args.iter().find(|(name, _)| *name == &captures[1]) {
+            Some((_, value)) => value.clone(),
+            None => captures[0].to_string(),
+        }
+    })
+    .into_owned()
+}
+
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::translate($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_only_translate_known_keys() {
+        let english = catalog(DEFAULT_LOCALE).unwrap();
+        for (lang, _) in CATALOGS {
+            for key in catalog(lang).unwrap().keys() {
+                assert!(
+                    english.contains_key(key),
+                    "{lang}.toml has unknown key {key}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fill_leaves_placeholders_in_values_alone() {
+        let args = [
+            ("filter", "{exercise}".to_string()),
+            ("exercise", "if1".to_string()),
+        ];
+        assert_eq!(
+            fill("{filter} matches {exercise}, not {other}", &args),
+            "{exercise} matches if1, not {other}"
+        );
+    }
+
+    #[test]
+    fn test_language_candidates() {
+        assert_eq!(candidates("fr_FR.UTF-8"), vec!["fr_fr", "fr"]);
+        assert_eq!(candidates("pt-BR"), vec!["pt_br", "pt"]);
+        assert_eq!(candidates("en"), vec!["en"]);
+    }
+}
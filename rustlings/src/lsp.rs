@@ -0,0 +1,380 @@
+use crate::exercise::{source_state, Exercise, State};
+use crate::run::reset;
+use crate::verify::verify_quietly;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+const SHOW_HINT: &str = "rustlings.showHint";
+const RESET_EXERCISE: &str = "rustlings.resetExercise";
+const VERIFY_EXERCISE: &str = "rustlings.verifyExercise";
+
+// LSP constants, see the Language Server Protocol specification
+const TEXT_DOCUMENT_SYNC_FULL: u8 = 1;
+const SEVERITY_INFORMATION: u8 = 3;
+const MESSAGE_TYPE_ERROR: u8 = 1;
+const MESSAGE_TYPE_INFO: u8 = 3;
+const METHOD_NOT_FOUND: i32 = -32601;
+
+/// A minimal language server speaking the Language Server Protocol over stdio.
+/// It reports `I AM NOT DONE` markers as diagnostics and offers code actions
+/// and code lenses for the exercise opened in the editor.
+pub struct LspServer<'a> {
+    exercises: &'a [Exercise],
+    // The contents of the documents opened in the editor, keyed by URI
+    documents: HashMap<String, String>,
+}
+
+impl<'a> LspServer<'a> {
+    pub fn new(exercises: &'a [Exercise]) -> LspServer<'a> {
+        LspServer {
+            exercises,
+            documents: HashMap::new(),
+        }
+    }
+
+    /// Serve requests until the client sends `exit` or closes stdin
+    pub fn serve(&mut self) -> io::Result<()> {
+        let stdin = io::stdin();
+        let stdout = io::stdout();
+        let mut reader = stdin.lock();
+        let mut writer = stdout.lock();
+
+        while let Some(message) = read_message(&mut reader)? {
+            let method = message["method"].as_str().unwrap_or_default();
+            if method == "exit" {
+                break;
+            }
+            for reply in self.handle(method, &message) {
+                write_message(&mut writer, &reply)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Handle a single request or notification, returning the messages to send back
+    fn handle(&mut self, method: &str, message: &Value) -> Vec<Value> {
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+        let mut outgoing = Vec::new();
+
+        let result = match method {
+            "initialize" => Some(json!({
+                "capabilities": {
+                    "textDocumentSync": {
+                        "openClose": true,
+                        "change": TEXT_DOCUMENT_SYNC_FULL,
+                        "save": true,
+                    },
+                    "codeActionProvider": true,
+                    "codeLensProvider": { "resolveProvider": false },
+                    "executeCommandProvider": {
+                        "commands": [SHOW_HINT, RESET_EXERCISE, VERIFY_EXERCISE],
+                    },
+                },
+                "serverInfo": { "name": "rustlings", "version": crate::VERSION },
+            })),
+            "shutdown" => Some(Value::Null),
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                let uri = document["uri"].as_str().unwrap_or_default();
+                let text = document["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri.to_string(), text.to_string());
+                outgoing.push(self.diagnostics(uri));
+                None
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                // We only advertise full document sync, so the last change holds the whole text
+                if let Some(text) = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                {
+                    self.documents.insert(uri.to_string(), text.to_string());
+                }
+                outgoing.push(self.diagnostics(uri));
+                None
+            }
+            "textDocument/didSave" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                outgoing.push(self.diagnostics(uri));
+                None
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                self.documents.remove(uri);
+                outgoing.push(publish_diagnostics(uri, Vec::new()));
+                None
+            }
+            "textDocument/codeLens" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                Some(self.code_lenses(uri))
+            }
+            "textDocument/codeAction" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                Some(self.code_actions(uri))
+            }
+            "workspace/executeCommand" => {
+                let command = params["command"].as_str().unwrap_or_default();
+                let name = params["arguments"][0].as_str().unwrap_or_default();
+                outgoing.push(self.execute_command(command, name));
+                Some(Value::Null)
+            }
+            _ => {
+                if let Some(id) = id {
+                    outgoing.insert(
+                        0,
+                        json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": {
+                                "code": METHOD_NOT_FOUND,
+                                "message": format!("Unsupported method: {method}"),
+                            },
+                        }),
+                    );
+                }
+                return outgoing;
+            }
+        };
+
+        // Notifications carry no id and must not be answered
+        if let (Some(id), Some(result)) = (id, result) {
+            outgoing.insert(0, json!({ "jsonrpc": "2.0", "id": id, "result": result }));
+        }
+        outgoing
+    }
+
+    // Find the exercise backing the document at the given URI
+    fn exercise_for(&self, uri: &str) -> Option<&'a Exercise> {
+        let path = uri_to_path(uri)?;
+        self.exercises
+            .iter()
+            .find(|e| Path::new(&path).ends_with(&e.path))
+    }
+
+    // The state of a document, preferring the editor's unsaved contents
+    fn document_state(&self, uri: &str, exercise: &Exercise) -> State {
+        match self.documents.get(uri) {
+            Some(text) => source_state(text),
+            None => exercise.state(),
+        }
+    }
+
+    fn diagnostics(&self, uri: &str) -> Value {
+        let diagnostics = match self.exercise_for(uri) {
+            Some(exercise) => marker_line(&self.document_state(uri, exercise))
+                .map(|line| vec![marker_diagnostic(line)])
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+        publish_diagnostics(uri, diagnostics)
+    }
+
+    fn code_lenses(&self, uri: &str) -> Value {
+        let exercise = match self.exercise_for(uri) {
+            Some(exercise) => exercise,
+            None => return json!([]),
+        };
+        let status = match self.document_state(uri, exercise) {
+            State::Done => t!("status_done"),
+            State::Pending(_) => t!("status_pending"),
+        };
+        let top = line_range(0);
+        json!([
+            {
+                "range": top,
+                "command": {
+                    "title": format!("rustlings: {} ({status})", exercise.name),
+                    "command": VERIFY_EXERCISE,
+                    "arguments": [exercise.name],
+                },
+            },
+            {
+                "range": top,
+                "command": {
+                    "title": t!("lsp_show_hint"),
+                    "command": SHOW_HINT,
+                    "arguments": [exercise.name],
+                },
+            },
+        ])
+    }
+
+    fn code_actions(&self, uri: &str) -> Value {
+        let exercise = match self.exercise_for(uri) {
+            Some(exercise) => exercise,
+            None => return json!([]),
+        };
+        let mut actions = vec![json!({
+            "title": t!("lsp_show_hint"),
+            "command": { "title": t!("lsp_show_hint"), "command": SHOW_HINT, "arguments": [exercise.name] },
+        })];
+        if let Some(line) = marker_line(&self.document_state(uri, exercise)) {
+            // Marking an exercise as done removes the whole `I AM NOT DONE` line
+            actions.push(json!({
+                "title": t!("lsp_mark_done"),
+                "kind": "quickfix",
+                "diagnostics": [marker_diagnostic(line)],
+                "edit": {
+                    "changes": {
+                        uri: [{
+                            "range": {
+                                "start": { "line": line, "character": 0 },
+                                "end": { "line": line + 1, "character": 0 },
+                            },
+                            "newText": "",
+                        }],
+                    },
+                },
+            }));
+        }
+        actions.push(json!({
+            "title": t!("lsp_reset"),
+            "command": {
+                "title": t!("lsp_reset"),
+                "command": RESET_EXERCISE,
+                "arguments": [exercise.name],
+            },
+        }));
+        Value::Array(actions)
+    }
+
+    fn execute_command(&self, command: &str, name: &str) -> Value {
+        let exercise = match self.exercises.iter().find(|e| e.name == name) {
+            Some(exercise) => exercise,
+            None => return show_message(MESSAGE_TYPE_ERROR, t!("exercise_not_found", name = name)),
+        };
+        match command {
+            SHOW_HINT => show_message(MESSAGE_TYPE_INFO, exercise.localized_hint().to_string()),
+            RESET_EXERCISE => match reset(exercise) {
+                Ok(()) => {
+                    show_message(MESSAGE_TYPE_INFO, t!("lsp_reset_done", exercise = exercise))
+                }
+                Err(()) => show_message(
+                    MESSAGE_TYPE_ERROR,
+                    t!("lsp_reset_failed", exercise = exercise),
+                ),
+            },
+            VERIFY_EXERCISE => match verify_quietly(exercise) {
+                Ok(()) => show_message(MESSAGE_TYPE_INFO, t!("lsp_verified", exercise = exercise)),
+                Err(output) => show_message(
+                    MESSAGE_TYPE_ERROR,
+                    t!(
+                        "lsp_verify_failed",
+                        exercise = exercise,
+                        output = console::strip_ansi_codes(&output)
+                    ),
+                ),
+            },
+            _ => show_message(
+                MESSAGE_TYPE_ERROR,
+                t!("lsp_unknown_command", command = command),
+            ),
+        }
+    }
+}
+
+// The zero-based line of the `I AM NOT DONE` marker, if any
+fn marker_line(state: &State) -> Option<usize> {
+    match state {
+        State::Done => None,
+        State::Pending(context) => context
+            .iter()
+            .find(|line| line.important)
+            .map(|line| line.number - 1),
+    }
+}
+
+fn marker_diagnostic(line: usize) -> Value {
+    json!({
+        "range": line_range(line),
+        "severity": SEVERITY_INFORMATION,
+        "source": "rustlings",
+        "message": t!("lsp_marker"),
+    })
+}
+
+fn line_range(line: usize) -> Value {
+    json!({
+        "start": { "line": line, "character": 0 },
+        "end": { "line": line, "character": u32::MAX },
+    })
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn show_message(kind: u8, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "window/showMessage",
+        "params": { "type": kind, "message": message },
+    })
+}
+
+// Convert a `file://` URI into a local path, decoding percent escapes
+fn uri_to_path(uri: &str) -> Option<String> {
+    let encoded = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut chars = encoded.bytes();
+    while let Some(byte) = chars.next() {
+        if byte == b'%' {
+            let hex: Vec<u8> = chars.by_ref().take(2).collect();
+            let decoded = std::str::from_utf8(&hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())?;
+            bytes.push(decoded);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // Windows URIs look like file:///C:/path
+    match path.strip_prefix('/') {
+        Some(rest) if rest.get(1..2) == Some(":") => Some(rest.to_string()),
+        _ => Some(path),
+    }
+}
+
+// Read one message framed by a `Content-Length` header.
+// Returns `None` once the client has closed the stream.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let length = content_length
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length"))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    writer.flush()
+}
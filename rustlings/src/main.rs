@@ -1,28 +1,51 @@
-use crate::exercise::{Exercise, ExerciseList};
+use crate::cohort::{cohort_report, Bundle};
+use crate::exercise::{Exercise, ExerciseList, Mode, Topic};
+use crate::graph::ExerciseGraph;
+use crate::lsp::LspServer;
 use crate::project::RustAnalyzerProject;
 use crate::run::{reset, run};
-use crate::verify::verify;
+use crate::stats::{Event, Stats, Summary};
+use crate::toolchain::{Toolchain, Version};
+use crate::topic::{topic_progress, Mastery};
+use crate::upgrade::Outcome;
+use crate::verify::{verify, verify_strict};
 use argh::FromArgs;
-use console::Emoji;
 use notify::DebouncedEvent;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::env;
 use std::ffi::OsStr;
 use std::fs;
 use std::io::{self, prelude::*};
-use std::path::Path;
-use std::process::{Command, Stdio};
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
+#[macro_use]
+mod i18n;
 #[macro_use]
 mod ui;
 
+mod bench;
+mod cohort;
 mod exercise;
+mod graph;
+mod lsp;
+mod mutate;
+mod pack;
 mod project;
+mod property;
+mod protected;
+mod review;
 mod run;
+mod stats;
+mod toolchain;
+mod topic;
+mod upgrade;
+mod variant;
 mod verify;
 
 // In sync with crate version
@@ -37,6 +60,10 @@ struct Args {
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// the language of rustlings' messages and hints, e.g. `fr`
+    /// (defaults to the RUSTLINGS_LANG environment variable)
+    #[argh(option)]
+    lang: Option<String>,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -51,17 +78,41 @@ enum Subcommands {
     Hint(HintArgs),
     List(ListArgs),
     Lsp(LspArgs),
+    Stats(StatsArgs),
+    Export(ExportArgs),
+    Report(ReportArgs),
+    Mutants(MutantsArgs),
+    Pack(PackArgs),
+    Unpack(UnpackArgs),
+    Init(InitArgs),
+    Upgrade(UpgradeArgs),
+    Review(ReviewArgs),
+    Generate(GenerateArgs),
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
 /// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+struct VerifyArgs {
+    #[argh(switch)]
+    /// ignore the `I AM NOT DONE` markers, compile and test every exercise
+    /// and report exercises marked as done that don't pass or lost tests
+    strict: bool,
+    #[argh(option)]
+    /// the rustup toolchain, like "1.70" or "nightly", or the directory of a
+    /// local toolchain to compile the exercises with instead of the default one
+    toolchain: Option<String>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "watch")]
 /// Reruns `verify` when files were edited
-struct WatchArgs {}
+struct WatchArgs {
+    #[argh(positional)]
+    /// only watch the exercise or topic with exactly this name, or the
+    /// exercises matching comma separated patterns like `list --filter`
+    filter: Option<String>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "run")]
@@ -70,11 +121,15 @@ struct RunArgs {
     #[argh(positional)]
     /// the name of the exercise
     name: String,
+    #[argh(switch, short = 'i')]
+    /// connect the exercise to the terminal, so that you can type its input
+    /// (only for exercises that run as a program, not for tested ones)
+    interactive: bool,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "reset")]
-/// Resets a single exercise using "git stash -- <filename>"
+/// Resets a single exercise to its pristine copy, or using "git stash -- <filename>"
 struct ResetArgs {
     #[argh(positional)]
     /// the name of the exercise
@@ -93,7 +148,127 @@ struct HintArgs {
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "lsp")]
 /// Enable rust-analyzer for exercises
-struct LspArgs {}
+struct LspArgs {
+    #[argh(switch)]
+    /// run a language server over stdio instead of generating rust-project.json
+    serve: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "stats")]
+/// Summarizes the time, failures and hints recorded in watch mode
+struct StatsArgs {
+    #[argh(option)]
+    /// write the statistics of every exercise to the given CSV file
+    csv: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "export")]
+/// Exports your progress and solutions into a bundle for your mentors
+struct ExportArgs {
+    #[argh(
+        option,
+        short = 'o',
+        default = "String::from(\"rustlings-progress.json\")"
+    )]
+    /// the file to write the bundle to
+    output: String,
+    #[argh(option)]
+    /// your name as shown to your mentors (defaults to `git config user.name`)
+    name: Option<String>,
+    #[argh(option)]
+    /// the key of your cohort to sign the bundle with
+    /// (defaults to the RUSTLINGS_COHORT_KEY environment variable)
+    key: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "report")]
+/// Merges the progress bundles of a cohort into a single report
+struct ReportArgs {
+    #[argh(positional)]
+    /// the bundles created by `rustlings export`
+    bundles: Vec<String>,
+    #[argh(option)]
+    /// the key the bundles were signed with
+    /// (defaults to the RUSTLINGS_COHORT_KEY environment variable)
+    key: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "mutants")]
+/// For exercise authors: checks that the tests of exercises
+/// catch small mutations of their reference solutions
+struct MutantsArgs {
+    #[argh(positional)]
+    /// the names of the exercises, defaults to all test exercises with a solution
+    names: Vec<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "pack")]
+/// Bundles info.toml, the exercises, their READMEs, hidden tests and
+/// solutions into a single archive
+struct PackArgs {
+    #[argh(option, short = 'o')]
+    /// the archive to write, defaults to "rustlings-<version>.tar.gz"
+    output: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "unpack")]
+/// Extracts everything from an archive made by `rustlings pack`
+struct UnpackArgs {
+    #[argh(positional)]
+    /// the archive to extract
+    archive: String,
+    #[argh(option, short = 'o', default = "String::from(\".\")")]
+    /// the directory to extract into, defaults to the current one
+    output: String,
+    #[argh(switch)]
+    /// overwrite the files that already exist in the directory
+    force: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "init")]
+/// Sets up a fresh workspace from an archive made by `rustlings pack`,
+/// without the solutions and without needing git
+struct InitArgs {
+    #[argh(positional)]
+    /// the archive to set up the workspace from
+    archive: String,
+    #[argh(positional, default = "String::from(\"rustlings\")")]
+    /// the directory of the workspace, defaults to "rustlings"
+    directory: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "upgrade")]
+/// Replaces the exercises with a new version from an archive made by
+/// `rustlings pack`, carrying your progress over
+struct UpgradeArgs {
+    #[argh(positional)]
+    /// the archive with the new version of the exercises
+    archive: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "generate")]
+/// Generates the templated exercises from your seed, again for the ones
+/// you didn't modify
+struct GenerateArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "review")]
+/// Solves a completed exercise again in a scratch copy when it's due for
+/// review, or checks the review in progress
+struct ReviewArgs {
+    #[argh(switch, short = 'l')]
+    /// show when the completed exercises are due for review instead
+    list: bool,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "list")]
@@ -115,62 +290,200 @@ struct ListArgs {
     #[argh(switch, short = 's')]
     /// display only exercises that have been solved
     solved: bool,
+    #[argh(option, short = 'g')]
+    /// show the dependency graph of the exercises instead,
+    /// either as `text` or in the `dot` format of Graphviz
+    graph: Option<String>,
+    #[argh(switch, short = 't')]
+    /// show the progress in each topic instead
+    by_topic: bool,
+    #[argh(option, short = 'r')]
+    /// write a Markdown report of the progress in each topic to the given file
+    report: Option<String>,
 }
 
 fn main() {
+    ui::init();
     let args: Args = argh::from_env();
 
+    let lang = args
+        .lang
+        .clone()
+        .or_else(|| env::var("RUSTLINGS_LANG").ok());
+    i18n::init(lang.as_deref());
+
     if args.version {
         println!("v{VERSION}");
         std::process::exit(0);
     }
 
     if args.nested.is_none() {
-        println!("\n{WELCOME}\n");
+        if ui::output().plain {
+            println!("\n{} rustlings\n", t!("welcome_to"));
+        } else {
+            println!("\n       {}\n{WELCOME}\n", t!("welcome_to"));
+        }
+    }
+
+    // Archives are unpacked where there is no exercise set yet
+    match &args.nested {
+        Some(Subcommands::Init(subargs)) => {
+            let dir = Path::new(&subargs.directory);
+            match pack::init(Path::new(&subargs.archive), dir) {
+                Ok(manifest) => {
+                    success!(t!(
+                        "init_done",
+                        path = dir.display(),
+                        rustlings = manifest.rustlings
+                    ));
+                    std::process::exit(0);
+                }
+                Err(e) => {
+                    println!("{}", t!("init_failed", error = e));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Subcommands::Unpack(subargs)) => {
+            let dir = Path::new(&subargs.output);
+            match pack::unpack(Path::new(&subargs.archive), dir, true, subargs.force)
+                .and_then(|_| pack::generate(dir))
+            {
+                Ok(_) => {
+                    println!("{}", t!("unpack_done", path = subargs.output));
+                    std::process::exit(0);
+                }
+                Err(e) => {
+                    println!("{}", t!("unpack_failed", error = e));
+                    std::process::exit(1);
+                }
+            }
+        }
+        _ => {}
     }
 
     if !Path::new("info.toml").exists() {
         println!(
-            "{} must be run from the rustlings directory",
-            std::env::current_exe().unwrap().to_str().unwrap()
+            "{}",
+            t!(
+                "wrong_dir",
+                exe = std::env::current_exe().unwrap().to_str().unwrap()
+            )
         );
-        println!("Try `cd rustlings/`!");
         std::process::exit(1);
     }
 
-    if !rustc_exists() {
-        println!("We cannot find `rustc`.");
-        println!("Try running `rustc --version` to diagnose your problem.");
-        println!("For instructions on how to install Rust, check the README.");
-        std::process::exit(1);
+    if let Some(Subcommands::Verify(VerifyArgs {
+        toolchain: Some(name),
+        ..
+    })) = &args.nested
+    {
+        toolchain::select(Toolchain::parse(name));
     }
+    let Some(installed) = toolchain::installed() else {
+        match toolchain::selected() {
+            Toolchain::Default => println!("{}", t!("rustc_missing")),
+            selected => println!("{}", t!("toolchain_missing", toolchain = selected)),
+        }
+        std::process::exit(1);
+    };
 
     let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
+    let ExerciseList {
+        exercises, topics, ..
+    } = ExerciseList::parse(toml_str).unwrap();
+    let graph = ExerciseGraph::new(&exercises, &topics).unwrap_or_else(|e| {
+        println!("{e}");
+        std::process::exit(1);
+    });
     let verbose = args.nocapture;
 
+    // Templated exercises are only generated by `init`, `unpack` and `generate`,
+    // and packed as templates
+    let ungenerated = variant::ungenerated(&exercises, Path::new(""));
+    let generating = matches!(
+        args.nested,
+        Some(Subcommands::Generate(_) | Subcommands::Pack(_))
+    );
+    if !ungenerated.is_empty() && !generating {
+        println!(
+            "{}",
+            t!("variant_not_generated", names = ungenerated.join(", "))
+        );
+        std::process::exit(1);
+    }
+
+    // Exercises that are still as shipped get a pristine copy, so that they can be
+    // reset, reviewed and checked for altered tests in workspaces like git clones
+    for exercise in &exercises {
+        let _ = exercise.keep_pristine();
+    }
+
+    let too_new: Vec<Version> = exercises
+        .iter()
+        .filter_map(toolchain::missing_requirement)
+        .collect();
+    if let Some(required) = too_new.iter().max() {
+        warn!(t!(
+            "toolchain_outdated",
+            installed = installed,
+            count = too_new.len(),
+            required = required
+        ));
+    }
+
     let command = args.nested.unwrap_or_else(|| {
-        println!("{DEFAULT_OUT}\n");
+        println!("{}\n", t!("default_out"));
         std::process::exit(0);
     });
     match command {
         Subcommands::List(subargs) => {
+            if let Some(format) = &subargs.graph {
+                match format.as_str() {
+                    "text" => print!("{}", graph.to_text()),
+                    "dot" => print!("{}", graph.to_dot()),
+                    _ => {
+                        println!("{}", t!("graph_unknown_format", format = format));
+                        std::process::exit(1);
+                    }
+                }
+                std::process::exit(0);
+            }
+            if let Some(path) = &subargs.report {
+                let progress = topic_progress(&exercises, &topics);
+                if let Err(e) = fs::write(path, topic::report(&progress)) {
+                    println!("{}", t!("report_write_failed", path = path, error = e));
+                    std::process::exit(1);
+                }
+                println!("{}", t!("report_written", path = path));
+                std::process::exit(0);
+            }
+            if subargs.by_topic {
+                list_topics(&exercises, &topics);
+                std::process::exit(0);
+            }
             if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
+                println!(
+                    "{:<17}\t{:<46}\t{:<7}",
+                    t!("list_name"),
+                    t!("list_path"),
+                    t!("list_status")
+                );
             }
             let mut exercises_done: u16 = 0;
-            let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
             exercises.iter().for_each(|e| {
                 let fname = format!("{}", e.path.display());
-                let filter_cond = filters
-                    .split(',')
-                    .filter(|f| !f.trim().is_empty())
-                    .any(|f| e.name.contains(&f) || fname.contains(&f));
+                let filter_cond = subargs
+                    .filter
+                    .as_deref()
+                    .is_some_and(|filter| matches_filter(e, filter));
                 let status = if e.looks_done() {
                     exercises_done += 1;
-                    "Done"
+                    t!("status_done")
+                } else if graph.is_unlocked(e) {
+                    t!("status_pending")
                 } else {
-                    "Pending"
+                    t!("status_locked")
                 };
                 let solve_cond = {
                     (e.looks_done() && subargs.solved)
@@ -202,126 +515,309 @@ fn main() {
             });
             let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
             println!(
-                "Progress: You completed {} / {} exercises ({:.1} %).",
-                exercises_done,
-                exercises.len(),
-                percentage_progress
+                "{}",
+                t!(
+                    "list_progress",
+                    done = exercises_done,
+                    total = exercises.len(),
+                    percentage = format!("{percentage_progress:.1}")
+                )
             );
             std::process::exit(0);
         }
 
         Subcommands::Run(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let exercise = find_exercise(&subargs.name, &exercises, &graph);
+            warn_if_locked(exercise, &graph);
 
-            run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+            run(exercise, verbose, subargs.interactive).unwrap_or_else(|_| std::process::exit(1));
         }
 
         Subcommands::Reset(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let exercise = find_exercise(&subargs.name, &exercises, &graph);
 
             reset(exercise).unwrap_or_else(|_| std::process::exit(1));
         }
 
         Subcommands::Hint(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let exercise = find_exercise(&subargs.name, &exercises, &graph);
 
-            println!("{}", exercise.hint);
+            println!("{}", exercise.localized_hint());
         }
 
-        Subcommands::Verify(_subargs) => {
+        Subcommands::Verify(subargs) => {
+            if subargs.strict {
+                verify_strict(&exercises).unwrap_or_else(|_| std::process::exit(1));
+                return;
+            }
             verify(&exercises, (0, exercises.len()), verbose)
                 .unwrap_or_else(|_| std::process::exit(1));
         }
 
-        Subcommands::Lsp(_subargs) => {
+        Subcommands::Stats(subargs) => {
+            let stats = Stats::load();
+            if let Some(path) = &subargs.csv {
+                if let Err(e) = fs::write(path, stats::to_csv(&stats, &exercises)) {
+                    println!("{}", t!("stats_csv_failed", path = path, error = e));
+                    std::process::exit(1);
+                }
+                println!("{}", t!("stats_csv_written", path = path));
+            } else if stats.exercises.is_empty() {
+                println!("{}", t!("stats_empty"));
+            } else {
+                print_stats(&stats, &exercises);
+            }
+        }
+
+        Subcommands::Export(subargs) => {
+            let learner = subargs.name.unwrap_or_else(learner_name);
+            let key = subargs
+                .key
+                .or_else(|| env::var("RUSTLINGS_COHORT_KEY").ok());
+            let bundle = Bundle::new(&exercises, learner, VERSION, key.as_deref());
+            if let Err(e) = fs::write(&subargs.output, bundle.to_json()) {
+                println!("{}", t!("export_failed", path = subargs.output, error = e));
+                std::process::exit(1);
+            }
+            println!("{}", t!("export_written", path = subargs.output));
+        }
+
+        Subcommands::Report(subargs) => {
+            let key = subargs
+                .key
+                .or_else(|| env::var("RUSTLINGS_COHORT_KEY").ok());
+            let mut cohort = Vec::new();
+            for path in &subargs.bundles {
+                match Bundle::load(path).and_then(|bundle| {
+                    bundle.verify(key.as_deref())?;
+                    Ok(bundle)
+                }) {
+                    Ok(bundle) => cohort.push(bundle.progress),
+                    Err(e) => warn!(t!("bundle_skipped", path = path, error = e)),
+                }
+            }
+            if cohort.is_empty() {
+                println!("{}", t!("report_no_bundles"));
+                std::process::exit(1);
+            }
+            print!("{}", cohort_report(&cohort));
+        }
+
+        Subcommands::Mutants(subargs) => {
+            let selected: Vec<&Exercise> = if subargs.names.is_empty() {
+                exercises
+                    .iter()
+                    .filter(|e| e.mode == Mode::Test && e.solution_path().exists())
+                    .collect()
+            } else {
+                subargs
+                    .names
+                    .iter()
+                    .map(|name| find_exercise(name, &exercises, &graph))
+                    .collect()
+            };
+            if selected.is_empty() {
+                println!("{}", t!("mutants_no_solutions"));
+                std::process::exit(1);
+            }
+            if !check_mutants(&selected) {
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Pack(subargs) => {
+            let output = subargs
+                .output
+                .unwrap_or_else(|| format!("rustlings-{VERSION}.tar.gz"));
+            let readmes: Vec<PathBuf> = topics.iter().filter_map(|t| t.readme.clone()).collect();
+            if let Err(e) = pack::pack(&exercises, &readmes, VERSION, Path::new(&output)) {
+                println!("{}", t!("pack_failed", path = output, error = e));
+                std::process::exit(1);
+            }
+            println!("{}", t!("pack_written", path = output));
+        }
+
+        Subcommands::Upgrade(subargs) => {
+            let outcomes = upgrade::upgrade(&exercises, Path::new(&subargs.archive))
+                .unwrap_or_else(|e| {
+                    println!("{}", t!("upgrade_failed", error = e));
+                    std::process::exit(1);
+                });
+            print_upgrade(&outcomes);
+            sync_rust_project();
+        }
+
+        Subcommands::Review(subargs) => {
+            if subargs.list {
+                review::list(&exercises);
+            } else if review::review(&exercises).is_err() {
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Generate(_) => {
+            if exercises.iter().all(|e| e.template.is_none()) {
+                println!("{}", t!("generate_none"));
+                std::process::exit(0);
+            }
+            let kept = variant::generate(&exercises, Path::new(""), true).unwrap_or_else(|e| {
+                println!("{}", t!("variant_failed", error = e));
+                std::process::exit(1);
+            });
+            for name in kept {
+                warn!(t!("generate_kept", name = name));
+            }
+            success!(t!("generate_done", seed = variant::seed(Path::new(""))));
+            sync_rust_project();
+        }
+
+        // Handled before info.toml is read
+        Subcommands::Init(_) | Subcommands::Unpack(_) => {}
+
+        Subcommands::Lsp(subargs) => {
+            if subargs.serve {
+                LspServer::new(&exercises).serve().unwrap_or_else(|e| {
+                    eprintln!("{}", t!("lsp_server_stopped", error = e));
+                    std::process::exit(1);
+                });
+                return;
+            }
+
             let mut project = RustAnalyzerProject::new();
-            project
+            let toolchain = project
                 .get_sysroot_src()
                 .expect("Couldn't find toolchain path, do you have `rustc` installed?");
+            println!("{}\n", t!("lsp_toolchain", toolchain = toolchain));
             project
-                .exercies_to_json()
+                .exercises_to_json(&exercises)
                 .expect("Couldn't parse rustlings exercises files");
 
             if project.crates.is_empty() {
-                println!("Failed find any exercises, make sure you're in the `rustlings` folder");
+                println!("{}", t!("lsp_no_exercises"));
             } else if project.write_to_disk().is_err() {
-                println!("Failed to write rust-project.json to disk for rust-analyzer");
+                println!("{}", t!("lsp_write_failed"));
             } else {
-                println!("Successfully generated rust-project.json");
-                println!("rust-analyzer will now parse exercises, restart your language server or editor")
+                println!("{}", t!("lsp_success"));
             }
         }
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose) {
-            Err(e) => {
+        Subcommands::Watch(subargs) => {
+            let watched: Vec<Exercise> = match &subargs.filter {
+                Some(filter) => watched_exercises(&exercises, filter),
+                None => exercises.clone(),
+            };
+            if watched.is_empty() {
                 println!(
-                    "Error: Could not watch your progress. Error message was {:?}.",
-                    e
+                    "{}",
+                    t!(
+                        "watch_no_match",
+                        filter = subargs.filter.unwrap_or_default()
+                    )
                 );
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
                 std::process::exit(1);
             }
-            Ok(WatchStatus::Finished) => {
-                println!(
-                    "{emoji} All exercises completed! {emoji}",
-                    emoji = Emoji("🎉", "★")
-                );
-                println!("\n{FENISH_LINE}\n");
-            }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+            match watch(&watched, &graph, verbose) {
+                Err(e) => {
+                    println!("{}", t!("watch_error", error = format!("{e:?}")));
+                    std::process::exit(1);
+                }
+                Ok(WatchStatus::Finished) if subargs.filter.is_some() => {
+                    println!(
+                        "{emoji} {} {emoji}",
+                        t!(
+                            "watch_subset_finished",
+                            filter = subargs.filter.unwrap_or_default()
+                        ),
+                        emoji = ui::emoji("🎉", "★")
+                    );
+                }
+                Ok(WatchStatus::Finished) => {
+                    println!(
+                        "{emoji} {} {emoji}",
+                        t!("watch_finished"),
+                        emoji = ui::emoji("🎉", "★")
+                    );
+                    if !ui::output().plain {
+                        println!("\n{FENISH_LINE}");
+                    }
+                    println!("\n{}\n", t!("finish_message"));
+                }
+                Ok(WatchStatus::Unfinished) => {
+                    println!("{}", t!("watch_unfinished"));
+                }
             }
-        },
+        }
     }
 }
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
-    println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
+// Whether the exercise matches one of the comma separated patterns, which
+// are part of its name or path, or its topic
+fn matches_filter(exercise: &Exercise, filter: &str) -> bool {
+    let path = exercise.path.display().to_string();
+    filter
+        .to_lowercase()
+        .split(',')
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .any(|f| {
+            exercise.name.contains(f) || path.contains(f) || exercise.topic.as_deref() == Some(f)
+        })
+}
+
+// The exercises `watch` is restricted to: the exercise or the topic with exactly
+// that name, or with a comma separated list, those matching one of its patterns
+fn watched_exercises(exercises: &[Exercise], filter: &str) -> Vec<Exercise> {
+    let filter = filter.trim();
+    exercises
+        .iter()
+        .filter(|e| {
+            if filter.contains(',') {
+                matches_filter(e, filter)
+            } else {
+                e.name == filter || e.topic.as_deref() == Some(filter)
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+fn spawn_watch_shell(failed_exercise: &Arc<Mutex<Option<Exercise>>>, should_quit: Arc<AtomicBool>) {
+    let failed_exercise = Arc::clone(failed_exercise);
+    println!("{}", t!("watch_welcome"));
     thread::spawn(move || loop {
         let mut input = String::new();
         match io::stdin().read_line(&mut input) {
             Ok(_) => {
                 let input = input.trim();
                 if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{hint}");
+                    if let Some(exercise) = &*failed_exercise.lock().unwrap() {
+                        println!("{}", exercise.localized_hint());
+                        stats::record(exercise, Event::HintViewed);
                     }
                 } else if input == "clear" {
-                    println!("\x1B[2J\x1B[1;1H");
+                    ui::clear_screen();
                 } else if input.eq("quit") {
                     should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
+                    println!("{}", t!("watch_bye"));
                 } else if input.eq("help") {
-                    println!("Commands available to you in watch mode:");
-                    println!("  hint  - prints the current exercise's hint");
-                    println!("  clear - clears the screen");
-                    println!("  quit  - quits watch mode");
-                    println!("  help  - displays this help message");
-                    println!();
-                    println!("Watch mode automatically re-evaluates the current exercise");
-                    println!("when you edit a file's contents.")
+                    println!("{}", t!("watch_help"));
                 } else {
-                    println!("unknown command: {input}");
+                    println!("{}", t!("watch_unknown_command", input = input));
                 }
             }
-            Err(error) => println!("error reading command: {error}"),
+            Err(error) => println!("{}", t!("watch_read_error", error = error)),
         }
     });
 }
 
-fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
+fn find_exercise<'a>(name: &str, exercises: &'a [Exercise], graph: &ExerciseGraph) -> &'a Exercise {
     if name.eq("next") {
         exercises
             .iter()
-            .find(|e| !e.looks_done())
+            .find(|e| !e.looks_done() && graph.is_unlocked(e))
             .unwrap_or_else(|| {
-                println!("🎉 Congratulations! You have done all the exercises!");
-                println!("🔚 There are no more exercises to do next!");
+                println!("{} {}", ui::emoji("🎉", "*"), t!("all_done"));
+                println!("{} {}", ui::emoji("🔚", "*"), t!("nothing_next"));
                 std::process::exit(1)
             })
     } else {
@@ -329,61 +825,226 @@ fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
             .iter()
             .find(|e| e.name == name)
             .unwrap_or_else(|| {
-                println!("No exercise found for '{name}'!");
+                println!("{}", t!("exercise_not_found", name = name));
                 std::process::exit(1)
             })
     }
 }
 
+fn list_topics(exercises: &[Exercise], topics: &[Topic]) {
+    println!(
+        "{:<24}\t{:<9}\t{:<20}\t{}",
+        t!("list_topic"),
+        t!("list_done"),
+        t!("report_quiz"),
+        t!("list_status")
+    );
+    let progress = topic_progress(exercises, topics);
+    for topic in &progress {
+        let done = format!("{}/{}", topic.done(), topic.exercises.len());
+        let quiz = topic.quiz.map(|quiz| quiz.name.as_str()).unwrap_or("-");
+        println!(
+            "{:<24}\t{done:<9}\t{quiz:<20}\t{}",
+            topic.name,
+            topic.mastery().label()
+        );
+    }
+    let mastered = progress
+        .iter()
+        .filter(|topic| topic.mastery() == Mastery::Mastered)
+        .count();
+    println!(
+        "{}",
+        t!(
+            "topics_progress",
+            mastered = mastered,
+            total = progress.len()
+        )
+    );
+}
+
+fn print_stats(stats: &Stats, exercises: &[Exercise]) {
+    println!(
+        "{:<24}\t{:<9}\t{:<9}\t{:<16}\t{:<13}\t{}",
+        t!("list_topic"),
+        t!("stats_completed"),
+        t!("stats_time"),
+        t!("stats_compile_failures"),
+        t!("stats_test_failures"),
+        t!("stats_hints")
+    );
+    let print_row = |label: &str, summary: Summary| {
+        let completed = format!("{}/{}", summary.completed, summary.total);
+        println!(
+            "{label:<24}\t{completed:<9}\t{:<9}\t{:<16}\t{:<13}\t{}",
+            stats::format_duration(summary.seconds),
+            summary.compile_failures,
+            summary.test_failures,
+            summary.hints_viewed
+        );
+    };
+
+    let mut topics: Vec<Option<&str>> = Vec::new();
+    for exercise in exercises {
+        if !topics.contains(&exercise.topic.as_deref()) {
+            topics.push(exercise.topic.as_deref());
+        }
+    }
+    for topic in topics {
+        let label = topic.map_or_else(|| t!("graph_no_topic"), String::from);
+        let in_topic = exercises.iter().filter(|e| e.topic.as_deref() == topic);
+        print_row(&label, Summary::new(stats, in_topic));
+    }
+    print_row(&t!("stats_total"), Summary::new(stats, exercises));
+}
+
+// Report the mutants of the solutions that the tests of the exercises
+// don't catch, returning whether all of them were caught
+fn check_mutants(exercises: &[&Exercise]) -> bool {
+    let mut all_caught = true;
+    for exercise in exercises {
+        let progress_bar = ui::spinner(t!("mutants_running", exercise = exercise.name));
+        let result = mutate::surviving_mutants(exercise);
+        progress_bar.finish_and_clear();
+        match result {
+            Ok((total, survivors)) if survivors.is_empty() => {
+                success!(t!(
+                    "mutants_all_caught",
+                    exercise = exercise.name,
+                    total = total
+                ));
+            }
+            Ok((total, survivors)) => {
+                all_caught = false;
+                warn!(t!(
+                    "mutants_survived",
+                    exercise = exercise.name,
+                    survived = survivors.len(),
+                    total = total
+                ));
+                let path = exercise.solution_path();
+                for mutant in survivors {
+                    let mutated = if mutant.mutated.is_empty() {
+                        t!("mutants_removed")
+                    } else {
+                        mutant.mutated.trim().to_string()
+                    };
+                    println!(
+                        "  {}:{}: {} => {mutated}",
+                        path.display(),
+                        mutant.line,
+                        mutant.original.trim()
+                    );
+                }
+            }
+            Err(e) => {
+                all_caught = false;
+                warn!(e);
+            }
+        }
+    }
+    all_caught
+}
+
+fn print_upgrade(outcomes: &[Outcome]) {
+    let mut kept = 0;
+    for outcome in outcomes {
+        match outcome {
+            Outcome::Kept(_) => kept += 1,
+            Outcome::Added(name) => println!("  + {}", t!("upgrade_added", exercise = name)),
+            Outcome::Renamed { from, to } => {
+                kept += 1;
+                println!("  ~ {}", t!("upgrade_renamed", from = from, to = to))
+            }
+            Outcome::Removed(name) => println!("  - {}", t!("upgrade_removed", exercise = name)),
+            Outcome::Changed {
+                name,
+                was_done,
+                backup,
+            } => {
+                let message = if *was_done {
+                    t!("upgrade_changed_done", exercise = name)
+                } else {
+                    t!("upgrade_changed", exercise = name)
+                };
+                warn!(message);
+                if let Some(backup) = backup {
+                    println!("    {}", t!("upgrade_backup", path = backup.display()));
+                }
+            }
+        }
+    }
+    success!(t!("upgrade_done", kept = kept));
+}
+
+// Exercises can be attempted in any order, but learners should know
+// when they're skipping ahead of what an exercise builds on
+fn warn_if_locked(exercise: &Exercise, graph: &ExerciseGraph) {
+    let missing = graph.missing_prerequisites(exercise);
+    if !missing.is_empty() {
+        let names: Vec<&str> = missing.iter().map(|e| e.name.as_str()).collect();
+        warn!(t!(
+            "exercise_locked",
+            exercise = exercise.name,
+            missing = names.join(", ")
+        ));
+    }
+}
+
 enum WatchStatus {
     Finished,
     Unfinished,
 }
 
-fn watch(exercises: &[Exercise], verbose: bool) -> notify::Result<WatchStatus> {
-    /* Clears the terminal with an ANSI escape code.
-    Works in UNIX and newer Windows terminals. */
-    fn clear_screen() {
-        println!("\x1Bc");
-    }
-
+fn watch(
+    exercises: &[Exercise],
+    graph: &ExerciseGraph,
+    verbose: bool,
+) -> notify::Result<WatchStatus> {
     let (tx, rx) = channel();
     let should_quit = Arc::new(AtomicBool::new(false));
 
     let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(2))?;
     watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
+    watcher.watch(Path::new("./info.toml"), RecursiveMode::NonRecursive)?;
 
-    clear_screen();
+    ui::clear_screen();
+    stats::start_tracking();
 
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let failed_exercise_hint = match verify(exercises.iter(), (0, exercises.len()), verbose) {
+    let failed_exercise = match verify(exercises.iter(), (0, exercises.len()), verbose) {
         Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
+        Err(exercise) => Arc::new(Mutex::new(Some(exercise.clone()))),
     };
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
+    spawn_watch_shell(&failed_exercise, Arc::clone(&should_quit));
     loop {
         match rx.recv_timeout(Duration::from_secs(1)) {
             Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
-                                    .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
-                            );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-                        match verify(pending_exercises, (num_done, exercises.len()), verbose) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
-                            }
+                DebouncedEvent::Create(b) | DebouncedEvent::Write(b)
+                    if b.file_name() == Some(OsStr::new("info.toml")) =>
+                {
+                    sync_rust_project();
+                }
+                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b)
+                    if b.extension() == Some(OsStr::new("rs")) && b.exists() =>
+                {
+                    let filepath = b.as_path().canonicalize().unwrap();
+                    let edited_exercise = exercises.iter().find(|e| filepath.ends_with(&e.path));
+                    // Continue with the unlocked exercises before the locked ones
+                    let (unlocked, locked): (Vec<&Exercise>, Vec<&Exercise>) = exercises
+                        .iter()
+                        .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path))
+                        .partition(|e| graph.is_unlocked(e));
+                    let pending_exercises =
+                        edited_exercise.into_iter().chain(unlocked).chain(locked);
+                    let num_done = exercises.iter().filter(|e| e.looks_done()).count();
+                    ui::clear_screen();
+                    if let Some(exercise) = edited_exercise {
+                        warn_if_locked(exercise, graph);
+                    }
+                    match verify(pending_exercises, (num_done, exercises.len()), verbose) {
+                        Ok(_) => return Ok(WatchStatus::Finished),
+                        Err(exercise) => {
+                            *failed_exercise.lock().unwrap() = Some(exercise.clone());
                         }
                     }
                 }
@@ -401,43 +1062,34 @@ fn watch(exercises: &[Exercise], verbose: bool) -> notify::Result<WatchStatus> {
     }
 }
 
-fn rustc_exists() -> bool {
-    Command::new("rustc")
-        .args(&["--version"])
-        .stdout(Stdio::null())
-        .spawn()
-        .and_then(|mut child| child.wait())
-        .map(|status| status.success())
-        .unwrap_or(false)
-}
-
-const DEFAULT_OUT: &str = r#"Thanks for installing Rustlings!
-
-Is this your first time? Don't worry, Rustlings was made for beginners! We are
-going to teach you a lot of things about Rust, but before we can get
-started, here's a couple of notes about how Rustlings operates:
-
-1. The central concept behind Rustlings is that you solve exercises. These
-   exercises usually have some sort of syntax error in them, which will cause
-   them to fail compilation or testing. Sometimes there's a logic error instead
-   of a syntax error. No matter what error, it's your job to find it and fix it!
-   You'll know when you fixed it because then, the exercise will compile and
-   Rustlings will be able to move on to the next exercise.
-2. If you run Rustlings in watch mode (which we recommend), it'll automatically
-   start with the first exercise. Don't get confused by an error message popping
-   up as soon as you run Rustlings! This is part of the exercise that you're
-   supposed to solve, so open the exercise file in an editor and start your
-   detective work!
-3. If you're stuck on an exercise, there is a helpful hint you can view by typing
-   'hint' (in watch mode), or running `rustlings hint exercise_name`.
-4. If an exercise doesn't make sense to you, feel free to open an issue on GitHub!
-   (https://github.com/rust-lang/rustlings/issues/new). We look at every issue,
-   and sometimes, other learners do too so you can help each other out!
-5. If you want to use `rust-analyzer` with exercises, which provides features like 
-   autocompletion, run the command `rustlings lsp`. 
-
-Got all that? Great! To get started, run `rustlings watch` in order to get the first
-exercise. Make sure to have your editor open!"#;
+// Keep rust-project.json in sync with info.toml when exercises are added,
+// but only if the learner opted in by running `rustlings lsp` before
+fn sync_rust_project() {
+    if !RustAnalyzerProject::exists_on_disk() {
+        return;
+    }
+    let exercises = fs::read_to_string("info.toml")
+        .ok()
+        .and_then(|toml_str| ExerciseList::parse(&toml_str).ok());
+    if let Some(list) = exercises {
+        if let Err(e) = project::regenerate(&list.exercises) {
+            println!("{}", t!("lsp_update_failed", error = e));
+        }
+    }
+}
+
+// The name learners are known by in their cohort, taken from their git configuration
+fn learner_name() -> String {
+    Command::new("git")
+        .args(["config", "user.name"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| env::var("USER").ok())
+        .unwrap_or_else(|| String::from("anonymous"))
+}
 
 const FENISH_LINE: &str = r#"+----------------------------------------------------+
 |          You made it to the Fe-nish line!          |
@@ -457,17 +1109,9 @@ const FENISH_LINE: &str = r#"+--------------------------------------------------
          ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒
        ▒▒    ▒▒    ▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒▒    ▒▒    ▒▒
        ▒▒  ▒▒    ▒▒                  ▒▒    ▒▒  ▒▒
-           ▒▒  ▒▒                      ▒▒  ▒▒
-
-We hope you enjoyed learning about the various aspects of Rust!
-If you noticed any issues, please don't hesitate to report them to our repo.
-You can also contribute your own exercises to help the greater community!
-
-Before reporting an issue or contributing, please read our guidelines:
-https://github.com/rust-lang/rustlings/blob/main/CONTRIBUTING.md"#;
+           ▒▒  ▒▒                      ▒▒  ▒▒"#;
 
-const WELCOME: &str = r#"       welcome to...
-                 _   _ _
+const WELCOME: &str = r#"                 _   _ _
   _ __ _   _ ___| |_| (_)_ __   __ _ ___
  | '__| | | / __| __| | | '_ \ / _` / __|
  | |  | |_| \__ \ |_| | | | | | (_| \__ \
@@ -0,0 +1,226 @@
+use crate::exercise::{Exercise, Mode};
+use regex::Regex;
+use std::env;
+use std::fs;
+use std::process;
+use std::time::{Duration, Instant};
+
+// Comparison operators and the operators they are flipped to.
+// Requiring the surrounding spaces of formatted code keeps generics like `Vec<T>` intact.
+const COMPARISONS: &[(&str, &str)] = &[
+    (" == ", " != "),
+    (" != ", " == "),
+    (" < ", " >= "),
+    (" >= ", " < "),
+    (" > ", " <= "),
+    (" <= ", " > "),
+];
+// Integer literals that aren't tuple indices like `.0` or part of a name
+const INTEGER_REGEX: &str = r"(?:^|[^\w.])(\d+)\b";
+const BOOLEAN_REGEX: &str = r"\b(true|false)\b";
+// Mutants may loop forever, so their tests are stopped once they take this many
+// times as long as with the solution, but never sooner than `MIN_TIMEOUT`
+const TIMEOUT_FACTOR: u32 = 10;
+const MIN_TIMEOUT: Duration = Duration::from_secs(1);
+// How long the tests may take with the solution itself
+const SOLUTION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A copy of a solution with a single line changed
+#[derive(Debug, PartialEq, Eq)]
+pub struct Mutant {
+    /// The one-based number of the changed line
+    pub line: usize,
+    pub original: String,
+    /// The changed line, empty if the statement was removed
+    pub mutated: String,
+}
+
+impl Mutant {
+    fn apply(&self, source: &str) -> String {
+        let mut lines: Vec<&str> = source.lines().collect();
+        lines[self.line - 1] = &self.mutated;
+        lines.join("\n") + "\n"
+    }
+}
+
+/// The mutants of the source outside of its tests, which are expected to
+/// come last: comparisons are flipped, constants changed, and statements removed
+pub fn mutants(source: &str) -> Vec<Mutant> {
+    let integer = Regex::new(INTEGER_REGEX).unwrap();
+    let boolean = Regex::new(BOOLEAN_REGEX).unwrap();
+    let mut mutants = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("#[cfg(test)]") || trimmed.starts_with("#[test]") {
+            break;
+        }
+        if trimmed.starts_with("//") || trimmed.starts_with("#[") || trimmed.starts_with("use ") {
+            continue;
+        }
+        let mut mutate = |mutated: String| {
+            mutants.push(Mutant {
+                line: index + 1,
+                original: line.to_string(),
+                mutated,
+            })
+        };
+
+        for (operator, flipped) in COMPARISONS {
+            for (position, _) in line.match_indices(operator) {
+                let end = position + operator.len();
+                mutate(format!("{}{flipped}{}", &line[..position], &line[end..]));
+            }
+        }
+        for captures in integer.captures_iter(line) {
+            let literal = captures.get(1).unwrap();
+            let value: u128 = match literal.as_str().parse() {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+            mutate(format!(
+                "{}{}{}",
+                &line[..literal.start()],
+                value.saturating_add(1),
+                &line[literal.end()..]
+            ));
+        }
+        for literal in boolean.find_iter(line) {
+            let negated = if literal.as_str() == "true" {
+                "false"
+            } else {
+                "true"
+            };
+            mutate(format!(
+                "{}{negated}{}",
+                &line[..literal.start()],
+                &line[literal.end()..]
+            ));
+        }
+        let is_statement = trimmed.ends_with(';')
+            && !trimmed.starts_with("let ")
+            && !trimmed.starts_with(['.', ')', '}', ']']);
+        if is_statement {
+            mutate(String::new());
+        }
+    }
+    mutants
+}
+
+// How the tests of an exercise went with another source
+enum Outcome {
+    /// The tests passed, in the given time
+    Passed(Duration),
+    Failed,
+    TimedOut,
+}
+
+// Compile the exercise with the given source and run its tests, stopping
+// them after the timeout
+fn test(exercise: &Exercise, source: &str, timeout: Duration) -> Outcome {
+    let path = env::temp_dir().join(format!("rustlings_mutant_{}.rs", process::id()));
+    if fs::write(&path, source).is_err() {
+        return Outcome::Failed;
+    }
+    let mutated = Exercise {
+        path: path.clone(),
+        ..exercise.clone()
+    };
+    let outcome = match mutated.compile() {
+        Ok(compiled) => {
+            let start = Instant::now();
+            match compiled.run_with_timeout(timeout) {
+                Some(true) => Outcome::Passed(start.elapsed()),
+                Some(false) => Outcome::Failed,
+                None => Outcome::TimedOut,
+            }
+        }
+        Err(_) => Outcome::Failed,
+    };
+    let _ = fs::remove_file(path);
+    outcome
+}
+
+/// Run the tests of the exercise against every mutant of its reference
+/// solution and return the mutants that survived, i.e. that still pass
+pub fn surviving_mutants(exercise: &Exercise) -> Result<(usize, Vec<Mutant>), String> {
+    if exercise.mode != Mode::Test {
+        return Err(t!("mutants_not_test", exercise = exercise.name));
+    }
+    let solution_path = exercise.solution_path();
+    let solution = fs::read_to_string(&solution_path).map_err(|_| {
+        t!(
+            "mutants_no_solution",
+            exercise = exercise.name,
+            path = solution_path.display()
+        )
+    })?;
+    let elapsed = match test(exercise, &solution, SOLUTION_TIMEOUT) {
+        Outcome::Passed(elapsed) => elapsed,
+        Outcome::Failed => {
+            return Err(t!("mutants_solution_fails", path = solution_path.display()));
+        }
+        Outcome::TimedOut => {
+            return Err(t!(
+                "mutants_solution_timeout",
+                path = solution_path.display(),
+                seconds = SOLUTION_TIMEOUT.as_secs()
+            ));
+        }
+    };
+
+    // Mutants whose tests time out are caught, like those whose tests fail
+    let timeout = (elapsed * TIMEOUT_FACTOR).max(MIN_TIMEOUT);
+    let mutants = mutants(&solution);
+    let total = mutants.len();
+    let survivors = mutants
+        .into_iter()
+        .filter(|mutant| {
+            matches!(
+                test(exercise, &mutant.apply(&solution), timeout),
+                Outcome::Passed(_)
+            )
+        })
+        .collect();
+    Ok((total, survivors))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mutants() {
+        let source = "fn price(quantity: u32) -> Vec<u32> {
+    // 40 is the limit
+    let limit = 40;
+    println!(\"{}\", t.0);
+    if quantity > limit { vec![quantity] } else { vec![quantity * 2] }
+}
+
+#[test]
+fn check() {
+    assert!(price(1) == vec![2]);
+}
+";
+        let mutants = mutants(source);
+        let mutated: Vec<(usize, &str)> = mutants
+            .iter()
+            .map(|mutant| (mutant.line, mutant.mutated.as_str()))
+            .collect();
+        assert_eq!(
+            mutated,
+            vec![
+                (3, "    let limit = 41;"),
+                (4, ""),
+                (
+                    5,
+                    "    if quantity <= limit { vec![quantity] } else { vec![quantity * 2] }"
+                ),
+                (
+                    5,
+                    "    if quantity > limit { vec![quantity] } else { vec![quantity * 3] }"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use crate::exercise::{Exercise, ExerciseList, PRISTINE_DIR, SOLUTIONS_DIR};
+use crate::variant;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// The version of the archive layout, bumped on incompatible changes
+const FORMAT: u32 = 1;
+const MANIFEST_PATH: &str = "rustlings-pack.toml";
+// The files about the whole exercise set, packed along with info.toml when they exist
+const TRACK_FILES: &[&str] = &["exercises/README.md"];
+
+/// Describes the archive, stored as its first entry
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct Manifest {
+    pub format: u32,
+    /// The version of rustlings that packed the archive
+    pub rustlings: String,
+}
+
+/// The files of the exercise set: info.toml, the track files, the READMEs of the
+/// topics, the files info.toml refers to and the reference solutions of the
+/// exercises. Nothing else is packed, like build output or the packer's own files.
+fn files(exercises: &[Exercise], readmes: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from("info.toml")];
+    let referenced = exercises
+        .iter()
+        .flat_map(|exercise| {
+            // The variants of templated exercises are generated by every learner
+            let mut paths: Vec<PathBuf> = exercise.template.iter().cloned().collect();
+            if exercise.template.is_none() {
+                paths.push(exercise.path.clone());
+            }
+            paths.extend(exercise.hidden_tests.clone());
+            paths.extend(exercise.deps.iter().map(|dep| dep.path.clone()));
+            paths.push(exercise.solution_path());
+            paths
+        })
+        .chain(readmes.iter().cloned())
+        .chain(TRACK_FILES.iter().map(PathBuf::from));
+    for path in referenced {
+        if path.is_file() && !files.contains(&path) {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Bundle the exercise set of the current directory into a gzipped tarball
+pub fn pack(
+    exercises: &[Exercise],
+    readmes: &[PathBuf],
+    version: &str,
+    output: &Path,
+) -> Result<(), String> {
+    // Exercises are packed as shipped, not with the packer's own changes
+    let modified = exercises
+        .iter()
+        .find(|e| e.sha256.is_some() && e.shipped_source(&e.path).is_none());
+    if let Some(exercise) = modified {
+        return Err(t!("pack_modified", exercise = exercise));
+    }
+    let manifest = Manifest {
+        format: FORMAT,
+        rustlings: version.to_string(),
+    };
+    let manifest = toml::to_string(&manifest).map_err(|e| e.to_string())?;
+    let file = File::create(output).map_err(|e| e.to_string())?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, MANIFEST_PATH, manifest.as_bytes())
+        .map_err(|e| e.to_string())?;
+    for path in files(exercises, readmes) {
+        builder
+            .append_path(&path)
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn open(archive: &Path) -> Result<tar::Archive<GzDecoder<File>>, String> {
+    let file = File::open(archive).map_err(|e| format!("{}: {e}", archive.display()))?;
+    Ok(tar::Archive::new(GzDecoder::new(file)))
+}
+
+/// Read the manifest of the archive and check that this version of rustlings understands it
+pub fn manifest(archive: &Path) -> Result<Manifest, String> {
+    let mut tarball = open(archive)?;
+    let mut entries = tarball.entries().map_err(|e| e.to_string())?;
+    let mut entry = entries
+        .next()
+        .and_then(Result::ok)
+        .filter(|entry| {
+            entry
+                .path()
+                .is_ok_and(|path| path == Path::new(MANIFEST_PATH))
+        })
+        .ok_or_else(|| t!("pack_not_archive", path = archive.display()))?;
+    let mut manifest = String::new();
+    entry
+        .read_to_string(&mut manifest)
+        .map_err(|e| e.to_string())?;
+    let manifest: Manifest = toml::from_str(&manifest).map_err(|e| e.to_string())?;
+    if manifest.format > FORMAT {
+        return Err(t!(
+            "pack_format_unsupported",
+            path = archive.display(),
+            rustlings = manifest.rustlings
+        ));
+    }
+    Ok(manifest)
+}
+
+// The paths of the files to extract from the archive
+fn extracted(archive: &Path, with_solutions: bool) -> Result<Vec<PathBuf>, String> {
+    let mut tarball = open(archive)?;
+    let mut paths = Vec::new();
+    for entry in tarball.entries().map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path().map_err(|e| e.to_string())?.into_owned();
+        let skipped = path == Path::new(MANIFEST_PATH)
+            || (!with_solutions && path.starts_with(SOLUTIONS_DIR));
+        if !skipped && !entry.header().entry_type().is_dir() {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+/// Extract the archive into the directory, leaving out the solutions unless asked for.
+/// Files that already exist in the directory are only overwritten with `force`.
+/// Paths escaping the directory are refused by `unpack_in`.
+pub fn unpack(
+    archive: &Path,
+    dir: &Path,
+    with_solutions: bool,
+    force: bool,
+) -> Result<Manifest, String> {
+    let manifest = manifest(archive)?;
+    let paths = extracted(archive, with_solutions)?;
+    let existing: Vec<String> = paths
+        .iter()
+        .filter(|path| dir.join(path).exists())
+        .map(|path| path.display().to_string())
+        .collect();
+    if !existing.is_empty() && !force {
+        return Err(t!("unpack_exists", paths = existing.join(", ")));
+    }
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let mut tarball = open(archive)?;
+    for entry in tarball.entries().map_err(|e| e.to_string())? {
+        let mut entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path().map_err(|e| e.to_string())?.into_owned();
+        if !paths.contains(&path) {
+            continue;
+        }
+        entry
+            .unpack_in(dir)
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+    }
+    Ok(manifest)
+}
+
+/// Generate the variants of the templated exercises unpacked into the directory
+pub fn generate(dir: &Path) -> Result<(), String> {
+    let info = fs::read_to_string(dir.join("info.toml")).map_err(|e| e.to_string())?;
+    let ExerciseList { exercises, .. } = ExerciseList::parse(&info).map_err(|e| e.to_string())?;
+    variant::generate(&exercises, dir, false)?;
+    Ok(())
+}
+
+/// Set up a fresh workspace in the directory from the archive. The shipped
+/// exercises are also stored as pristine copies, so that `reset` and
+/// `verify --strict` work without git.
+pub fn init(archive: &Path, dir: &Path) -> Result<Manifest, String> {
+    let not_empty = fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_some());
+    if not_empty {
+        return Err(t!("init_not_empty", path = dir.display()));
+    }
+    let manifest = unpack(archive, dir, false, false)?;
+
+    let info = fs::read_to_string(dir.join("info.toml")).map_err(|e| e.to_string())?;
+    let ExerciseList { exercises, .. } = ExerciseList::parse(&info).map_err(|e| e.to_string())?;
+    for exercise in &exercises {
+        // Generated below from the learner's own seed
+        if exercise.template.is_some() {
+            continue;
+        }
+        let pristine = dir.join(PRISTINE_DIR).join(&exercise.path);
+        if let Some(parent) = pristine.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::copy(dir.join(&exercise.path), pristine).map_err(|e| e.to_string())?;
+    }
+    variant::generate(&exercises, dir, false)?;
+    Ok(manifest)
+}
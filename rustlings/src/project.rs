@@ -1,7 +1,12 @@
-use glob::glob;
+use crate::exercise::Exercise;
+use crate::toolchain;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
-use std::process::Command;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+const PROJECT_JSON_PATH: &str = "./rust-project.json";
 
 /// Contains the structure of resulting rust-project.json file
 /// and functions to build the data required to create the file
@@ -13,12 +18,22 @@ pub struct RustAnalyzerProject {
 
 #[derive(Serialize, Deserialize)]
 pub struct Crate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
     root_module: String,
     edition: String,
-    deps: Vec<String>,
+    deps: Vec<Dep>,
     cfg: Vec<String>,
 }
 
+/// A dependency of a crate, referring to another crate by its index
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Dep {
+    #[serde(rename = "crate")]
+    krate: usize,
+    name: String,
+}
+
 impl RustAnalyzerProject {
     pub fn new() -> RustAnalyzerProject {
         RustAnalyzerProject {
@@ -27,44 +42,77 @@ impl RustAnalyzerProject {
         }
     }
 
+    /// Whether rust-project.json has been generated before
+    pub fn exists_on_disk() -> bool {
+        Path::new(PROJECT_JSON_PATH).exists()
+    }
+
     /// Write rust-project.json to disk
     pub fn write_to_disk(&self) -> Result<(), std::io::Error> {
         std::fs::write(
-            "./rust-project.json",
+            PROJECT_JSON_PATH,
             serde_json::to_vec(&self).expect("Failed to serialize to JSON"),
         )?;
         Ok(())
     }
 
-    /// If path contains .rs extension, add a crate to `rust-project.json`
-    fn path_to_json(&mut self, path: String) {
-        if let Some((_, ext)) = path.split_once('.') {
-            if ext == "rs" {
-                self.crates.push(Crate {
-                    root_module: path,
-                    edition: "2021".to_string(),
-                    deps: Vec::new(),
-                    // This allows rust_analyzer to work inside #[test] blocks
-                    cfg: vec!["test".to_string()],
-                })
-            }
-        }
+    /// Add a crate to `rust-project.json` and return its index
+    fn add_crate(
+        &mut self,
+        display_name: &str,
+        root_module: &Path,
+        edition: &str,
+        deps: Vec<Dep>,
+    ) -> usize {
+        self.crates.push(Crate {
+            display_name: Some(display_name.to_string()),
+            root_module: root_module.to_string_lossy().to_string(),
+            edition: edition.to_string(),
+            deps,
+            // This allows rust_analyzer to work inside #[test] blocks
+            cfg: vec!["test".to_string()],
+        });
+        self.crates.len() - 1
     }
 
-    /// Parse the exercises folder for .rs files, any matches will create
-    /// a new `crate` in rust-project.json which allows rust-analyzer to
-    /// treat it like a normal binary
-    pub fn exercies_to_json(&mut self) -> Result<(), Box<dyn Error>> {
-        for e in glob("./exercises/**/*")? {
-            let path = e?.to_string_lossy().to_string();
-            self.path_to_json(path);
+    /// Add a crate for every exercise in the order of info.toml. This allows
+    /// rust-analyzer to treat each exercise like a normal binary, which
+    /// depends on the exercise's declared deps. The standard library is
+    /// added by rust-analyzer itself from `sysroot_src`.
+    pub fn exercises_to_json(&mut self, exercises: &[Exercise]) -> Result<(), Box<dyn Error>> {
+        // Several exercises may share the same library, which only needs a single crate
+        let mut libraries: HashMap<PathBuf, usize> = HashMap::new();
+
+        for exercise in exercises {
+            if exercise.path.extension() != Some(OsStr::new("rs")) {
+                continue;
+            }
+
+            let mut deps = Vec::new();
+            for dep in &exercise.deps {
+                let krate = match libraries.get(&dep.path) {
+                    Some(&krate) => krate,
+                    None => {
+                        let krate =
+                            self.add_crate(&dep.name, &dep.path, exercise.edition(), Vec::new());
+                        libraries.insert(dep.path.clone(), krate);
+                        krate
+                    }
+                };
+                deps.push(Dep {
+                    krate,
+                    name: dep.name.clone(),
+                });
+            }
+
+            self.add_crate(&exercise.name, &exercise.path, exercise.edition(), deps);
         }
         Ok(())
     }
 
-    /// Use `rustc` to determine the default toolchain
-    pub fn get_sysroot_src(&mut self) -> Result<(), Box<dyn Error>> {
-        let toolchain = Command::new("rustc")
+    /// Use `rustc` to determine the default toolchain and return its path
+    pub fn get_sysroot_src(&mut self) -> Result<String, Box<dyn Error>> {
+        let toolchain = toolchain::rustc()
             .arg("--print")
             .arg("sysroot")
             .output()?
@@ -75,9 +123,7 @@ impl RustAnalyzerProject {
 
         let toolchain = whitespace_iter.next().unwrap_or(&toolchain);
 
-        println!("Determined toolchain: {}\n", &toolchain);
-
-        self.sysroot_src = (std::path::Path::new(&*toolchain)
+        self.sysroot_src = (std::path::Path::new(toolchain)
             .join("lib")
             .join("rustlib")
             .join("src")
@@ -85,6 +131,74 @@ impl RustAnalyzerProject {
             .join("library")
             .to_string_lossy())
         .to_string();
-        Ok(())
+        Ok(toolchain.to_string())
+    }
+}
+
+/// Regenerate rust-project.json for the given exercises
+pub fn regenerate(exercises: &[Exercise]) -> Result<(), Box<dyn Error>> {
+    let mut project = RustAnalyzerProject::new();
+    project.get_sysroot_src()?;
+    project.exercises_to_json(exercises)?;
+    project.write_to_disk()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Dependency;
+
+    fn exercise(name: &str, path: &str, deps: &[(&str, &str)]) -> Exercise {
+        Exercise {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            deps: deps
+                .iter()
+                .map(|(name, path)| Dependency {
+                    name: name.to_string(),
+                    path: PathBuf::from(path),
+                })
+                .collect(),
+            edition: Some("2018".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_exercises_to_json() {
+        let mut project = RustAnalyzerProject::new();
+        let exercises = [
+            exercise(
+                "first",
+                "exercises/first.rs",
+                &[("shared", "libs/shared.rs")],
+            ),
+            exercise("notes", "exercises/notes.md", &[]),
+            exercise(
+                "second",
+                "exercises/second.rs",
+                &[("shared", "libs/shared.rs")],
+            ),
+        ];
+        project.exercises_to_json(&exercises).unwrap();
+
+        let names: Vec<&str> = project
+            .crates
+            .iter()
+            .map(|krate| krate.display_name.as_deref().unwrap())
+            .collect();
+        assert_eq!(names, ["shared", "first", "second"]);
+        let deps = |index: usize| -> Vec<(usize, &str)> {
+            project.crates[index]
+                .deps
+                .iter()
+                .map(|dep| (dep.krate, dep.name.as_str()))
+                .collect()
+        };
+        assert!(deps(0).is_empty());
+        assert_eq!(deps(1), [(0, "shared")]);
+        assert_eq!(deps(2), [(0, "shared")]);
+        assert_eq!(project.crates[1].edition, "2018");
     }
 }
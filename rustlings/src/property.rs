@@ -0,0 +1,49 @@
+// The property-testing helper is kept in its own file, so that it's checked
+// like the rest of the code by the tests below
+const HELPER: &str = include_str!("../harness/property.rs");
+
+/// The helper module appended to test-mode exercises. Its items are imported
+/// into the exercise, so that `check` is in scope of its tests through
+/// `use super::*`, while items of the exercise with the same name take precedence.
+pub fn prelude() -> String {
+    format!(
+        "\n#[cfg(test)]\n#[allow(unused_imports)]\nuse rustlings_property::*;\n\n\
+         #[cfg(test)]\n#[allow(dead_code)]\nmod rustlings_property {{\n{HELPER}}}\n"
+    )
+}
+
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../harness/property.rs"]
+mod helper;
+
+#[cfg(test)]
+mod test {
+    use super::helper::*;
+
+    #[test]
+    fn test_shrink_integer() {
+        let (minimal, _) = shrink(&|x: u32| x < 1000, 123_456);
+        assert_eq!(minimal, 1000);
+        let (minimal, _) = shrink(&|x: i64| x > -10, -5_000);
+        assert_eq!(minimal, -10);
+    }
+
+    #[test]
+    fn test_shrink_vec() {
+        let (minimal, _) = shrink(&|v: Vec<u8>| v.len() < 3, vec![5, 9, 7, 1, 8]);
+        assert_eq!(minimal, [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_same_seed_same_inputs() {
+        let inputs = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..20)
+                .map(|size| <(i32, String)>::arbitrary(&mut rng, size))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(inputs(42), inputs(42));
+        assert_ne!(inputs(42), inputs(43));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::exercise::Exercise;
+use regex::Regex;
+use std::fs;
+
+/// The protected items of the exercise that differ from the pristine source.
+/// Items are compared token by token, so formatting changes and comments
+/// don't count as alterations. Deleted items count as altered.
+/// Without a pristine source there is nothing to compare with, and `None`
+/// is returned for exercises with protected items.
+pub fn altered_items(exercise: &Exercise) -> Option<Vec<&str>> {
+    match fs::read_to_string(&exercise.path) {
+        Ok(source) => altered_in(exercise, &source),
+        Err(_) => Some(Vec::new()),
+    }
+}
+
+/// Like `altered_items`, for another copy of the exercise
+pub fn altered_in<'a>(exercise: &'a Exercise, source: &str) -> Option<Vec<&'a str>> {
+    if exercise.protected.is_empty() {
+        return Some(Vec::new());
+    }
+    let pristine = exercise.pristine_source()?;
+    let altered = exercise
+        .protected
+        .iter()
+        .filter(|name| match find_item(&pristine, name) {
+            Some(expected) => {
+                let item = find_item(source, name);
+                item.map(|item| normalize(&item)) != Some(normalize(&expected))
+            }
+            // Items the pristine source doesn't have can't be checked
+            None => false,
+        })
+        .map(String::as_str)
+        .collect();
+    Some(altered)
+}
+
+// The source of the module or function with the given name,
+// from its attributes up to its closing brace
+fn find_item(source: &str, name: &str) -> Option<String> {
+    let re = Regex::new(&format!(
+        r"(?m)^[ \t]*(?:#\[[^\]]*\]\s*)*(?:pub(?:\([^)]*\))?\s+)?(?:mod|fn)\s+{}\b",
+        regex::escape(name)
+    ))
+    .unwrap();
+    let start = re.find(source)?.start();
+    let end = start + item_length(&source[start..])?;
+    Some(source[start..end].to_string())
+}
+
+// The length of the item at the start of the source up to its matching
+// closing brace, skipping braces in comments, strings and characters
+fn item_length(source: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut chars = source.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '/' if chars.peek().map(|&(_, next)| next) == Some('/') => {
+                while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+            }
+            '/' if chars.peek().map(|&(_, next)| next) == Some('*') => {
+                let mut previous = ' ';
+                for (_, c) in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '"' => {
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            // Character literals like '{', but not lifetimes like 'a
+            '\'' if source[i..].chars().nth(2) == Some('\'') => {
+                chars.next();
+                chars.next();
+            }
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Strip the comments and the whitespace that doesn't separate words,
+// outside of string literals
+fn normalize(item: &str) -> String {
+    let mut normalized = String::with_capacity(item.len());
+    let mut chars = item.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '"' => {
+                normalized.push(c);
+                while let Some(c) = chars.next() {
+                    normalized.push(c);
+                    match c {
+                        '\\' => normalized.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            // Character literals like '"', but not lifetimes like 'a
+            '\'' if chars.clone().nth(1) == Some('\'') => {
+                normalized.push(c);
+                normalized.extend(chars.next());
+                normalized.extend(chars.next());
+            }
+            c if c.is_whitespace() => {
+                // Keep words like `mut x` apart, but drop the spaces around punctuation
+                let after_word = normalized.ends_with(is_word_char);
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if after_word && chars.peek().is_some_and(|&c| is_word_char(c)) {
+                    normalized.push(' ');
+                }
+            }
+            c => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SOURCE: &str = r#"fn main() {}
+
+#[cfg(test)]
+mod tests {
+    // A brace in a comment: {
+    #[test]
+    fn braces() {
+        assert_eq!("}", format!("{}", '}'));
+    }
+}
+"#;
+
+    #[test]
+    fn test_find_item() {
+        let item = find_item(SOURCE, "tests").unwrap();
+        assert!(item.starts_with("#[cfg(test)]"));
+        assert!(item.ends_with("    }\n}"));
+        assert_eq!(find_item(SOURCE, "test"), None);
+    }
+
+    #[test]
+    fn test_normalize_ignores_formatting() {
+        let reformatted = "#[test] fn braces() { assert_eq!( \"}\",format!(\"{}\", '}') ); }";
+        let item = find_item(SOURCE, "braces").unwrap();
+        assert_eq!(normalize(&item), normalize(reformatted));
+        assert_ne!(normalize(&item), normalize(&item.replace("'}'", "'{'")));
+    }
+}
@@ -0,0 +1,287 @@
+use crate::exercise::{source_state, Exercise, Mode, State};
+use crate::protected;
+use crate::stats::{self, ExerciseStats, Stats};
+use crate::ui;
+use crate::verify;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const SCHEDULE_PATH: &str = ".rustlings/review.json";
+// Where exercises are solved again, leaving the learner's answers alone
+const SCRATCH_DIR: &str = ".rustlings/review";
+const DAY: u64 = 24 * 60 * 60;
+// The bounds of the ease factor, as in the SM-2 algorithm
+const MIN_EASE: f64 = 1.3;
+const MAX_EASE: f64 = 2.5;
+// Reviews that take this many attempts start over with a short interval
+const LAPSE_FAILURES: u32 = 3;
+
+/// When a completed exercise is due for review, and how quickly the time
+/// between its reviews grows
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Card {
+    /// Seconds since the UNIX epoch
+    pub due: u64,
+    /// Days from the last review to the next one
+    pub interval: u32,
+    /// How much the interval grows after a review, lower for harder exercises
+    pub ease: f64,
+    pub reviews: u32,
+    /// Failed attempts at the review in progress
+    pub failures: u32,
+}
+
+impl Card {
+    /// The first review of an exercise is sooner, and the following ones grow
+    /// slower, the more failures and hints it took to complete it
+    pub fn new(stats: Option<&ExerciseStats>, now: u64) -> Card {
+        let stats = stats.cloned().unwrap_or_default();
+        let difficulty = stats.compile_failures + stats.test_failures + 2 * stats.hints_viewed;
+        let interval = match difficulty {
+            0 => 4,
+            1..=5 => 2,
+            _ => 1,
+        };
+        Card {
+            due: stats.completed.unwrap_or(now) + u64::from(interval) * DAY,
+            interval,
+            ease: (MAX_EASE - 0.1 * f64::from(difficulty)).max(MIN_EASE),
+            reviews: 0,
+            failures: 0,
+        }
+    }
+
+    /// Schedule the next review after a successful one, depending on how many
+    /// attempts it took
+    pub fn reviewed(&mut self, now: u64) {
+        let failures = f64::from(self.failures);
+        self.ease = (self.ease + 0.1 - 0.15 * failures).clamp(MIN_EASE, MAX_EASE);
+        self.interval = if self.failures >= LAPSE_FAILURES {
+            1
+        } else {
+            ((f64::from(self.interval) * self.ease).round() as u32).max(self.interval + 1)
+        };
+        self.due = now + u64::from(self.interval) * DAY;
+        self.reviews += 1;
+        self.failures = 0;
+    }
+}
+
+/// The review cards of the completed exercises, keyed by exercise name
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Schedule {
+    pub cards: BTreeMap<String, Card>,
+    /// The exercise being reviewed
+    pub current: Option<String>,
+}
+
+impl Schedule {
+    pub fn load() -> Schedule {
+        fs::read_to_string(SCHEDULE_PATH)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        if let Some(dir) = Path::new(SCHEDULE_PATH).parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(SCHEDULE_PATH, serde_json::to_string_pretty(self)?)
+    }
+
+    /// Add cards for the exercises completed since the last review
+    fn update(&mut self, exercises: &[Exercise], stats: &Stats, now: u64) {
+        for exercise in exercises.iter().filter(|e| reviewable(e) && e.looks_done()) {
+            self.cards
+                .entry(exercise.name.clone())
+                .or_insert_with(|| Card::new(stats.get(exercise), now));
+        }
+    }
+
+    /// The most overdue exercise
+    fn next_due<'a>(&self, exercises: &'a [Exercise], now: u64) -> Option<&'a Exercise> {
+        exercises
+            .iter()
+            .filter_map(|exercise| Some((self.cards.get(&exercise.name)?, exercise)))
+            .filter(|(card, _)| card.due <= now)
+            .min_by_key(|(card, _)| card.due)
+            .map(|(_, exercise)| exercise)
+    }
+}
+
+// Clippy exercises are always linted in place, so they can't be solved elsewhere
+fn reviewable(exercise: &Exercise) -> bool {
+    exercise.mode != Mode::Clippy
+}
+
+/// Where the exercise is solved again during its review
+pub fn scratch_path(exercise: &Exercise) -> PathBuf {
+    Path::new(SCRATCH_DIR).join(&exercise.path)
+}
+
+/// Check the review in progress, or start the review of the most overdue exercise
+pub fn review(exercises: &[Exercise]) -> Result<(), ()> {
+    let now = stats::now();
+    let mut schedule = Schedule::load();
+    schedule.update(exercises, &Stats::load(), now);
+    let current = schedule
+        .current
+        .as_ref()
+        .and_then(|name| exercises.iter().find(|e| &e.name == name));
+    let result = match current {
+        Some(exercise) if scratch_path(exercise).exists() => check(&mut schedule, exercise, now),
+        _ => start(&mut schedule, exercises, now),
+    };
+    if let Err(error) = schedule.save() {
+        warn!(t!("review_save_failed", error = error));
+        return Err(());
+    }
+    result
+}
+
+// Verify the new solution of the exercise under review
+fn check(schedule: &mut Schedule, exercise: &Exercise, now: u64) -> Result<(), ()> {
+    let scratch = Exercise {
+        path: scratch_path(exercise),
+        ..exercise.clone()
+    };
+    let source = fs::read_to_string(&scratch.path).unwrap_or_default();
+    // Reviews start from the pristine copy, so there is always one to compare with
+    let altered = protected::altered_in(exercise, &source).unwrap_or_default();
+    if !altered.is_empty() {
+        warn!(t!(
+            "protected_altered",
+            exercise = scratch,
+            items = altered.join(", ")
+        ));
+        return Err(());
+    }
+
+    let progress_bar = ui::spinner(t!("review_checking", exercise = scratch));
+    let result = verify::verify_quietly(&scratch);
+    progress_bar.finish_and_clear();
+
+    let card = schedule
+        .cards
+        .entry(exercise.name.clone())
+        .or_insert_with(|| Card::new(None, now));
+    if let Err(output) = result {
+        card.failures += 1;
+        warn!(t!("review_failed", exercise = scratch));
+        println!("{output}");
+        return Err(());
+    }
+    if source_state(&source) != State::Done {
+        println!("{}", t!("review_not_done", exercise = scratch));
+        return Ok(());
+    }
+    card.reviewed(now);
+    let date = stats::format_date(card.due);
+    schedule.current = None;
+    let _ = fs::remove_file(&scratch.path);
+    success!(t!("review_passed", exercise = exercise, date = date));
+    Ok(())
+}
+
+// Restore the pristine version of the most overdue exercise to the scratch area
+fn start(schedule: &mut Schedule, exercises: &[Exercise], now: u64) -> Result<(), ()> {
+    schedule.current = None;
+    let Some(exercise) = schedule.next_due(exercises, now) else {
+        match schedule.cards.values().map(|card| card.due).min() {
+            Some(due) => println!(
+                "{}",
+                t!("review_nothing_due", date = stats::format_date(due))
+            ),
+            None => println!("{}", t!("review_no_cards")),
+        }
+        return Ok(());
+    };
+    let Some(pristine) = exercise.pristine_source() else {
+        warn!(t!("review_no_pristine", exercise = exercise));
+        return Err(());
+    };
+    let path = scratch_path(exercise);
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, pristine));
+    if let Err(error) = written {
+        warn!(t!("review_write_failed", error = error));
+        return Err(());
+    }
+    schedule.current = Some(exercise.name.clone());
+    println!(
+        "{}",
+        t!("review_started", exercise = exercise, path = path.display())
+    );
+    Ok(())
+}
+
+/// Show when the completed exercises are due for review
+pub fn list(exercises: &[Exercise]) {
+    let now = stats::now();
+    let mut schedule = Schedule::load();
+    schedule.update(exercises, &Stats::load(), now);
+    // Saved, so that exercises without a completion time keep their due date
+    let _ = schedule.save();
+    if schedule.cards.is_empty() {
+        println!("{}", t!("review_no_cards"));
+        return;
+    }
+    println!(
+        "{:<17}\t{:<10}\t{}",
+        t!("list_name"),
+        t!("review_due"),
+        t!("review_reviews")
+    );
+    for exercise in exercises {
+        if let Some(card) = schedule.cards.get(&exercise.name) {
+            let due = if card.due <= now {
+                t!("review_due_now")
+            } else {
+                stats::format_date(card.due)
+            };
+            println!("{:<17}\t{due:<10}\t{}", exercise.name, card.reviews);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_harder_exercises_are_due_sooner() {
+        let easy = Card::new(None, 0);
+        let hard = Card::new(
+            Some(&ExerciseStats {
+                completed: Some(0),
+                compile_failures: 4,
+                hints_viewed: 2,
+                ..Default::default()
+            }),
+            0,
+        );
+        assert!(hard.due < easy.due);
+        assert!(hard.ease < easy.ease);
+    }
+
+    #[test]
+    fn test_reviewed() {
+        let mut card = Card::new(None, 0);
+        card.reviewed(0);
+        assert_eq!(card.interval, 10);
+        assert_eq!(card.due, 10 * DAY);
+
+        card.failures = LAPSE_FAILURES;
+        card.reviewed(0);
+        assert_eq!(card.interval, 1);
+        assert_eq!(card.failures, 0);
+        assert_eq!(card.reviews, 2);
+    }
+}
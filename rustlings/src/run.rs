@@ -1,32 +1,77 @@
+use std::fs;
+use std::path::Path;
 use std::process::Command;
 
-use crate::exercise::{Exercise, Mode};
-use crate::verify::test;
-use indicatif::ProgressBar;
+use crate::exercise::{Exercise, Mode, PRISTINE_DIR};
+use crate::ui;
+use crate::verify::{bench, check_protected, check_toolchain, compile_fail, test};
 
 // Invoke the rust compiler on the path of the given exercise,
 // and run the ensuing binary.
 // The verbose argument helps determine whether or not to show
 // the output from the test harnesses (if the mode of the exercise is test)
-pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
+pub fn run(exercise: &Exercise, verbose: bool, interactive: bool) -> Result<(), ()> {
+    if interactive && !matches!(exercise.mode, Mode::Compile | Mode::Clippy) {
+        warn!(t!("run_not_interactive", exercise = exercise));
+        return Err(());
+    }
+    check_toolchain(exercise)?;
+    check_protected(exercise)?;
     match exercise.mode {
-        Mode::Test => test(exercise, verbose)?,
+        Mode::Test | Mode::Unsafe | Mode::Doctest => test(exercise, verbose)?,
+        Mode::Bench => bench(exercise)?,
+        Mode::CompileFail => compile_fail(exercise)?,
+        Mode::Compile | Mode::Clippy if interactive => run_interactively(exercise)?,
         Mode::Compile => compile_and_run(exercise)?,
         Mode::Clippy => compile_and_run(exercise)?,
     }
     Ok(())
 }
 
-// Resets the exercise by stashing the changes.
+// Resets the exercise by restoring its pristine copy if there is one,
+// as in workspaces set up by `rustlings init`, or else by stashing the changes.
+// Nothing is printed, as the language server resets exercises too.
 pub fn reset(exercise: &Exercise) -> Result<(), ()> {
-    let command = Command::new("git")
+    let pristine = Path::new(PRISTINE_DIR).join(&exercise.path);
+    if pristine.exists() {
+        return fs::copy(pristine, &exercise.path)
+            .map(|_| ())
+            .map_err(|_| ());
+    }
+
+    let stashed = Command::new("git")
         .args(["stash", "--"])
         .arg(&exercise.path)
-        .spawn();
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if stashed {
+        Ok(())
+    } else {
+        Err(())
+    }
+}
+
+// Compile the given exercise and run it connected to the terminal,
+// so that learners can type into it instead of the `stdin` of info.toml
+fn run_interactively(exercise: &Exercise) -> Result<(), ()> {
+    let progress_bar = ui::spinner(t!("compiling", exercise = exercise));
+    let compilation_result = exercise.compile();
+    progress_bar.finish_and_clear();
+    let compilation = match compilation_result {
+        Ok(compilation) => compilation,
+        Err(output) => {
+            warn!(t!("compilation_failed", exercise = exercise));
+            println!("{}", output.stderr);
+            return Err(());
+        }
+    };
 
-    match command {
-        Ok(_) => Ok(()),
-        Err(_) => Err(()),
+    if compilation.run_interactively() {
+        success!(t!("successfully_ran", exercise = exercise));
+        Ok(())
+    } else {
+        warn!(t!("ran_with_errors", exercise = exercise));
+        Err(())
     }
 }
 
@@ -34,39 +79,34 @@ pub fn reset(exercise: &Exercise) -> Result<(), ()> {
 // and run the ensuing binary.
 // This is strictly for non-test binaries, so output is displayed
 fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+    let progress_bar = ui::spinner(t!("compiling", exercise = exercise));
 
     let compilation_result = exercise.compile();
     let compilation = match compilation_result {
         Ok(compilation) => compilation,
         Err(output) => {
             progress_bar.finish_and_clear();
-            warn!(
-                "Compilation of {} failed!, Compiler error message:\n",
-                exercise
-            );
+            warn!(t!("compilation_failed", exercise = exercise));
             println!("{}", output.stderr);
             return Err(());
         }
     };
 
-    progress_bar.set_message(format!("Running {exercise}..."));
+    progress_bar.set_message(t!("running", exercise = exercise));
     let result = compilation.run();
     progress_bar.finish_and_clear();
 
     match result {
         Ok(output) => {
             println!("{}", output.stdout);
-            success!("Successfully ran {}", exercise);
+            success!(t!("successfully_ran", exercise = exercise));
             Ok(())
         }
         Err(output) => {
             println!("{}", output.stdout);
             println!("{}", output.stderr);
 
-            warn!("Ran {} with errors", exercise);
+            warn!(t!("ran_with_errors", exercise = exercise));
             Err(())
         }
     }
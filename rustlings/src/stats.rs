@@ -0,0 +1,288 @@
+use crate::exercise::Exercise;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const STATS_PATH: &str = ".rustlings/stats.json";
+// Written first and then renamed over the statistics, so that they are never half written
+const STATS_TMP_PATH: &str = ".rustlings/stats.json.tmp";
+
+// Only `watch` records statistics, so that `verify` in CI or a quick
+// `run` doesn't distort how long the exercises took
+static TRACKING: AtomicBool = AtomicBool::new(false);
+// Held while the statistics are loaded, updated and saved, as the watcher and
+// the watch shell record events from different threads
+static UPDATING: Mutex<()> = Mutex::new(());
+
+/// Something that happened while the learner worked on an exercise
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The exercise became the one the learner is working on
+    Started,
+    CompileFailed,
+    TestFailed,
+    HintViewed,
+    /// The exercise compiles, passes and isn't marked as not done anymore
+    Completed,
+}
+
+/// What was recorded about a single exercise.
+/// Times are seconds since the UNIX epoch.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct ExerciseStats {
+    pub started: Option<u64>,
+    pub completed: Option<u64>,
+    pub compile_failures: u32,
+    pub test_failures: u32,
+    pub hints_viewed: u32,
+}
+
+impl ExerciseStats {
+    /// How long the exercise took from start to completion
+    pub fn duration(&self) -> Option<u64> {
+        Some(self.completed?.saturating_sub(self.started?))
+    }
+
+    fn apply(&mut self, event: Event, now: u64) {
+        match event {
+            Event::Started => {
+                self.started.get_or_insert(now);
+            }
+            Event::CompileFailed => self.compile_failures += 1,
+            Event::TestFailed => self.test_failures += 1,
+            Event::HintViewed => self.hints_viewed += 1,
+            // Exercises that were already done before tracking began
+            // don't have a meaningful completion time
+            Event::Completed => {
+                if self.started.is_some() {
+                    self.completed.get_or_insert(now);
+                }
+            }
+        }
+    }
+}
+
+/// The statistics of all exercises, keyed by exercise name
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Stats {
+    pub exercises: BTreeMap<String, ExerciseStats>,
+}
+
+impl Stats {
+    pub fn load() -> Stats {
+        fs::read_to_string(STATS_PATH)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        if let Some(dir) = Path::new(STATS_PATH).parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(STATS_TMP_PATH, serde_json::to_string_pretty(self)?)?;
+        fs::rename(STATS_TMP_PATH, STATS_PATH)
+    }
+
+    pub fn get(&self, exercise: &Exercise) -> Option<&ExerciseStats> {
+        self.exercises.get(&exercise.name)
+    }
+}
+
+/// Start recording events, see `record`
+pub fn start_tracking() {
+    TRACKING.store(true, Ordering::SeqCst);
+}
+
+/// Record an event for the exercise in `.rustlings/stats.json`, if tracking
+pub fn record(exercise: &Exercise, event: Event) {
+    if !TRACKING.load(Ordering::SeqCst) {
+        return;
+    }
+    let _updating = UPDATING.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut stats = Stats::load();
+    let entry = stats.exercises.entry(exercise.name.clone()).or_default();
+    let before = entry.clone();
+    entry.apply(event, now());
+    // Statistics are a nice-to-have, failing to save them shouldn't interrupt the learner
+    if *entry != before {
+        let _ = stats.save();
+    }
+}
+
+/// Move the statistics of renamed exercises over to their new names
+pub fn rename(renames: &[(&str, &str)]) -> io::Result<()> {
+    let _updating = UPDATING.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut stats = Stats::load();
+    let mut moved = false;
+    for (from, to) in renames {
+        if let Some(entry) = stats.exercises.remove(*from) {
+            stats.exercises.insert(to.to_string(), entry);
+            moved = true;
+        }
+    }
+    if moved {
+        stats.save()?;
+    }
+    Ok(())
+}
+
+/// The current time in seconds since the UNIX epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// The totals over a group of exercises
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub completed: usize,
+    pub total: usize,
+    pub seconds: u64,
+    pub compile_failures: u32,
+    pub test_failures: u32,
+    pub hints_viewed: u32,
+}
+
+impl Summary {
+    pub fn new<'a>(stats: &Stats, exercises: impl IntoIterator<Item = &'a Exercise>) -> Summary {
+        let mut summary = Summary::default();
+        for exercise in exercises {
+            summary.total += 1;
+            let Some(entry) = stats.get(exercise) else {
+                continue;
+            };
+            if let Some(duration) = entry.duration() {
+                summary.completed += 1;
+                summary.seconds += duration;
+            }
+            summary.compile_failures += entry.compile_failures;
+            summary.test_failures += entry.test_failures;
+            summary.hints_viewed += entry.hints_viewed;
+        }
+        summary
+    }
+}
+
+/// Format a number of seconds like `1h 05m` or `3m 20s`
+pub fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds:02}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+// Format seconds since the UNIX epoch as an ISO 8601 date and time in UTC.
+// See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let time = timestamp % 86400;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+/// Format seconds since the UNIX epoch as an ISO 8601 date in UTC
+pub fn format_date(timestamp: u64) -> String {
+    let mut date = format_timestamp(timestamp);
+    date.truncate("YYYY-MM-DD".len());
+    date
+}
+
+// Quote a CSV field if needed, see RFC 4180
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// One CSV row per exercise with the raw statistics
+pub fn to_csv(stats: &Stats, exercises: &[Exercise]) -> String {
+    let mut csv = String::from(
+        "exercise,topic,started,completed,seconds,compile_failures,test_failures,hints_viewed\n",
+    );
+    for exercise in exercises {
+        let entry = stats.get(exercise).cloned().unwrap_or_default();
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{}",
+            csv_field(&exercise.name),
+            csv_field(exercise.topic.as_deref().unwrap_or_default()),
+            optional(entry.started.map(format_timestamp)),
+            optional(entry.completed.map(format_timestamp)),
+            optional(entry.duration().map(|d| d.to_string())),
+            entry.compile_failures,
+            entry.test_failures,
+            entry.hints_viewed
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_completion_requires_start() {
+        let mut entry = ExerciseStats::default();
+        entry.apply(Event::Completed, 100);
+        assert_eq!(entry.completed, None);
+
+        entry.apply(Event::Started, 100);
+        entry.apply(Event::CompileFailed, 130);
+        entry.apply(Event::Started, 140);
+        entry.apply(Event::Completed, 190);
+        entry.apply(Event::Completed, 200);
+        assert_eq!(entry.duration(), Some(90));
+        assert_eq!(entry.compile_failures, 1);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(200), "3m 20s");
+        assert_eq!(format_duration(3900), "1h 05m");
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_827_696), "2000-02-29T12:34:56Z");
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("quiz1"), "quiz1");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+}
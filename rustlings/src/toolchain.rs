@@ -0,0 +1,219 @@
+use crate::exercise::{Exercise, SANITIZER_ARGS};
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+static TOOLCHAIN: OnceLock<Toolchain> = OnceLock::new();
+static INSTALLED: OnceLock<Option<Version>> = OnceLock::new();
+static MIRI: OnceLock<bool> = OnceLock::new();
+static SANITIZER: OnceLock<bool> = OnceLock::new();
+
+/// The toolchain that compiles the exercises
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Toolchain {
+    /// Whatever `rustc` and `cargo` are on the PATH
+    Default,
+    /// A toolchain installed with rustup, like `nightly` or `1.70`
+    Rustup(String),
+    /// The directory of a local toolchain, containing `bin/rustc`
+    Local(PathBuf),
+}
+
+impl Toolchain {
+    /// A rustup toolchain name, or the path to a toolchain directory or its `rustc`
+    pub fn parse(arg: &str) -> Toolchain {
+        let path = Path::new(arg);
+        if path.is_dir() {
+            Toolchain::Local(path.to_path_buf())
+        } else if path.is_file() {
+            // `<toolchain>/bin/rustc`
+            let dir = path
+                .parent()
+                .and_then(Path::parent)
+                .unwrap_or(Path::new("."));
+            Toolchain::Local(dir.to_path_buf())
+        } else {
+            Toolchain::Rustup(arg.to_string())
+        }
+    }
+
+    fn command(&self, program: &str) -> Command {
+        match self {
+            Toolchain::Default => Command::new(program),
+            Toolchain::Rustup(name) => {
+                let mut command = Command::new(program);
+                command.arg(format!("+{name}"));
+                command
+            }
+            Toolchain::Local(dir) => {
+                let bin = dir.join("bin");
+                let mut command = Command::new(bin.join(program));
+                // So that cargo picks the rustc and clippy of the toolchain
+                command.env("RUSTC", bin.join("rustc"));
+                let mut paths = vec![bin];
+                paths.extend(env::var_os("PATH").iter().flat_map(env::split_paths));
+                if let Ok(path) = env::join_paths(paths) {
+                    command.env("PATH", path);
+                }
+                command
+            }
+        }
+    }
+}
+
+impl Display for Toolchain {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Toolchain::Default => write!(f, "rustc"),
+            Toolchain::Rustup(name) => write!(f, "{name}"),
+            Toolchain::Local(dir) => write!(f, "{}", dir.display()),
+        }
+    }
+}
+
+/// Compile the exercises with another toolchain than the default one.
+/// Has to be called before anything is compiled.
+pub fn select(toolchain: Toolchain) {
+    let _ = TOOLCHAIN.set(toolchain);
+}
+
+pub fn selected() -> &'static Toolchain {
+    TOOLCHAIN.get_or_init(|| Toolchain::Default)
+}
+
+/// A `rustc` command of the selected toolchain
+pub fn rustc() -> Command {
+    selected().command("rustc")
+}
+
+/// A `rustdoc` command of the selected toolchain
+pub fn rustdoc() -> Command {
+    selected().command("rustdoc")
+}
+
+/// A `cargo` command of the selected toolchain
+pub fn cargo() -> Command {
+    selected().command("cargo")
+}
+
+/// A rustc version like `1.70.0`, ignoring pre-release suffixes like `-nightly`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    /// Parse `1.70` or `1.70.0`, also from the output of `rustc --version`
+    pub fn parse(version: &str) -> Option<Version> {
+        let version = version.trim().trim_start_matches("rustc ");
+        let version = version.split([' ', '-']).next()?;
+        let mut parts = version.split('.').map(str::parse::<u32>);
+        let major = parts.next()?.ok()?;
+        let minor = parts.next()?.ok()?;
+        let patch = match parts.next() {
+            Some(patch) => patch.ok()?,
+            None => 0,
+        };
+        Some(Version {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The version of the selected toolchain, or `None` if its `rustc` doesn't run
+pub fn installed() -> Option<Version> {
+    *INSTALLED.get_or_init(|| {
+        let output = rustc().arg("--version").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Version::parse(&String::from_utf8_lossy(&output.stdout))
+    })
+}
+
+/// Whether the selected toolchain has the Miri component
+pub fn has_miri() -> bool {
+    *MIRI.get_or_init(|| {
+        cargo()
+            .args(["miri", "--version"])
+            .output()
+            .is_ok_and(|output| output.status.success())
+    })
+}
+
+/// Whether the selected toolchain can build with AddressSanitizer, which
+/// takes a nightly toolchain and a supported target
+pub fn has_sanitizer() -> bool {
+    *SANITIZER.get_or_init(|| {
+        rustc()
+            .args(SANITIZER_ARGS)
+            .args(["--print", "cfg"])
+            .output()
+            .is_ok_and(|output| {
+                output.status.success()
+                    && String::from_utf8_lossy(&output.stdout).contains("sanitize=\"address\"")
+            })
+    })
+}
+
+// The first rustc version supporting the edition
+fn edition_version(edition: &str) -> Option<Version> {
+    match edition {
+        "2018" => Version::parse("1.31"),
+        "2021" => Version::parse("1.56"),
+        "2024" => Version::parse("1.85"),
+        _ => None,
+    }
+}
+
+/// The oldest rustc the exercise works with, given its `min_rustc` and its edition
+pub fn required(exercise: &Exercise) -> Option<Version> {
+    let min_rustc = exercise.min_rustc.as_deref().and_then(Version::parse);
+    let edition = exercise.edition.as_deref().and_then(edition_version);
+    min_rustc.max(edition)
+}
+
+/// The required version if the selected toolchain is too old for the exercise
+pub fn missing_requirement(exercise: &Exercise) -> Option<Version> {
+    let required = required(exercise)?;
+    match installed() {
+        Some(installed) if installed >= required => None,
+        _ => Some(required),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        let version = Version::parse("rustc 1.77.0-nightly (635124704 2024-01-27)").unwrap();
+        assert_eq!(version.to_string(), "1.77.0");
+        assert_eq!(Version::parse("1.70"), Version::parse("1.70.0"));
+        assert!(Version::parse("1.9").unwrap() < Version::parse("1.10").unwrap());
+        assert_eq!(Version::parse("stable"), None);
+    }
+
+    #[test]
+    fn test_required_version() {
+        let exercise = Exercise {
+            min_rustc: Some("1.40".to_string()),
+            edition: Some("2021".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(required(&exercise), Version::parse("1.56"));
+    }
+}
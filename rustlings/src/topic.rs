@@ -0,0 +1,152 @@
+use crate::exercise::{Exercise, Topic};
+use crate::verify::verify_quietly;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// How far a learner got with a topic
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mastery {
+    NotStarted,
+    InProgress,
+    /// All exercises are done, but the quiz of the topic doesn't pass yet
+    QuizPending,
+    Mastered,
+}
+
+impl Mastery {
+    pub fn label(self) -> String {
+        match self {
+            Mastery::NotStarted => t!("topic_not_started"),
+            Mastery::InProgress => t!("topic_in_progress"),
+            Mastery::QuizPending => t!("topic_quiz_pending"),
+            Mastery::Mastered => t!("topic_mastered"),
+        }
+    }
+}
+
+/// The progress of the learner in a single topic
+pub struct TopicProgress<'a> {
+    pub name: &'a str,
+    pub readme: Option<PathBuf>,
+    pub exercises: Vec<&'a Exercise>,
+    pub quiz: Option<&'a Exercise>,
+    /// Whether the quiz is done and actually compiles and passes its tests.
+    /// Only checked once all exercises of the topic are done.
+    pub quiz_passed: bool,
+}
+
+impl<'a> TopicProgress<'a> {
+    pub fn done(&self) -> usize {
+        self.exercises.iter().filter(|e| e.looks_done()).count()
+    }
+
+    pub fn mastery(&self) -> Mastery {
+        let done = self.done();
+        if done < self.exercises.len() {
+            if done == 0 {
+                Mastery::NotStarted
+            } else {
+                Mastery::InProgress
+            }
+        } else if self.quiz.is_some() && !self.quiz_passed {
+            Mastery::QuizPending
+        } else {
+            Mastery::Mastered
+        }
+    }
+
+    fn quiz_label(&self) -> String {
+        match self.quiz {
+            None => "-".to_string(),
+            Some(quiz) if self.quiz_passed => t!("quiz_passed", quiz = quiz.name),
+            Some(quiz) => t!("quiz_not_passed", quiz = quiz.name),
+        }
+    }
+}
+
+/// The progress in every topic: the topics declared in info.toml first,
+/// then the topics only named by exercises
+pub fn topic_progress<'a>(
+    exercises: &'a [Exercise],
+    topics: &'a [Topic],
+) -> Vec<TopicProgress<'a>> {
+    let mut names: Vec<&str> = topics.iter().map(|t| t.name.as_str()).collect();
+    for name in exercises.iter().filter_map(|e| e.topic.as_deref()) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    // Quizzes are shared between topics, so each one is only compiled once
+    let mut quiz_results: HashMap<&str, bool> = HashMap::new();
+    names
+        .into_iter()
+        .map(|name| {
+            let topic = topics.iter().find(|t| t.name == name);
+            let quiz = topic
+                .and_then(|t| t.quiz.as_deref())
+                .and_then(|quiz| exercises.iter().find(|e| e.name == quiz));
+            let mut progress = TopicProgress {
+                name,
+                readme: topic.and_then(Topic::localized_readme),
+                exercises: exercises
+                    .iter()
+                    .filter(|e| e.topic.as_deref() == Some(name))
+                    .collect(),
+                quiz,
+                quiz_passed: false,
+            };
+            if let Some(quiz) = quiz {
+                if progress.done() == progress.exercises.len() {
+                    progress.quiz_passed = *quiz_results
+                        .entry(quiz.name.as_str())
+                        .or_insert_with(|| quiz.looks_done() && verify_quietly(quiz).is_ok());
+                }
+            }
+            progress
+        })
+        .collect()
+}
+
+/// Render the progress as a Markdown report that learners can share with their mentors
+pub fn report(progress: &[TopicProgress]) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "# {}\n", t!("report_title"));
+    let _ = writeln!(
+        report,
+        "| {} | {} | {} | {} |",
+        t!("list_topic"),
+        t!("report_exercises"),
+        t!("report_quiz"),
+        t!("list_status")
+    );
+    report.push_str("|---|---|---|---|\n");
+    for topic in progress {
+        let _ = writeln!(
+            report,
+            "| {} | {} / {} | {} | {} |",
+            topic.name,
+            topic.done(),
+            topic.exercises.len(),
+            topic.quiz_label(),
+            topic.mastery().label()
+        );
+    }
+
+    for topic in progress {
+        let _ = writeln!(report, "\n## {}\n", topic.name);
+        if let Some(readme) = &topic.readme {
+            let _ = writeln!(report, "README: {}\n", readme.display());
+        }
+        for exercise in &topic.exercises {
+            let marker = if exercise.looks_done() { "x" } else { " " };
+            let _ = writeln!(report, "- [{marker}] {}", exercise.name);
+        }
+        if let Some(quiz) = topic.quiz {
+            let marker = if topic.quiz_passed { "x" } else { " " };
+            let _ = writeln!(report, "- [{marker}] {} ({})", quiz.name, t!("report_quiz"));
+        }
+    }
+    report
+}
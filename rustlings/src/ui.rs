@@ -1,33 +1,178 @@
-macro_rules! warn {
-    ($fmt:literal, $ex:expr) => {{
-        use console::{style, Emoji};
-        use std::env;
-        let formatstr = format!($fmt, $ex);
+use console::Emoji;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::sync::OnceLock;
+
+const CONFIG_PATH: &str = ".rustlings/config.toml";
+
+static OUTPUT: OnceLock<OutputConfig> = OnceLock::new();
+
+/// How rustlings prints to the terminal.
+/// Read from `.rustlings/config.toml`, and overridden by environment variables.
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// Use colors (disabled by `NO_COLOR`)
+    pub color: bool,
+    /// Use emoji (disabled by `NO_EMOJI`)
+    pub emoji: bool,
+    /// Use bold colors on solid backgrounds (enabled by `RUSTLINGS_HIGH_CONTRAST`)
+    pub high_contrast: bool,
+    /// Print screen reader friendly plain text without progress bars,
+    /// spinners, ASCII art or screen clearing (enabled by `RUSTLINGS_PLAIN`)
+    pub plain: bool,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        OutputConfig {
+            color: true,
+            emoji: true,
+            high_contrast: false,
+            plain: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct ConfigFile {
+    #[serde(default)]
+    output: OutputConfig,
+}
+
+impl OutputConfig {
+    fn load() -> OutputConfig {
+        let mut config = fs::read_to_string(CONFIG_PATH)
+            .ok()
+            .and_then(|toml_str| toml::from_str::<ConfigFile>(&toml_str).ok())
+            .unwrap_or_default()
+            .output;
+
+        // See https://no-color.org
+        if env::var("NO_COLOR").is_ok_and(|value| !value.is_empty()) {
+            config.color = false;
+        }
         if env::var("NO_EMOJI").is_ok() {
-            println!("{} {}", style("!").red(), style(formatstr).red());
-        } else {
+            config.emoji = false;
+        }
+        if env::var("RUSTLINGS_HIGH_CONTRAST").is_ok() {
+            config.high_contrast = true;
+        }
+        if env::var("RUSTLINGS_PLAIN").is_ok() {
+            config.plain = true;
+        }
+        if config.plain {
+            config.color = false;
+            config.emoji = false;
+        }
+        config
+    }
+}
+
+/// Load the output configuration and apply it to the terminal. Called first
+/// thing in `main`, so that everything printed follows it.
+pub fn init() {
+    output();
+}
+
+/// The output configuration, see `init`
+pub fn output() -> &'static OutputConfig {
+    OUTPUT.get_or_init(|| {
+        let config = OutputConfig::load();
+        if !config.color {
+            console::set_colors_enabled(false);
+            console::set_colors_enabled_stderr(false);
+        }
+        config
+    })
+}
+
+/// Pick the emoji or its textual fallback according to the output configuration
+pub fn emoji<'a>(emoji: &'a str, fallback: &'a str) -> String {
+    if output().emoji {
+        Emoji(emoji, fallback).to_string()
+    } else {
+        fallback.to_string()
+    }
+}
+
+/// A spinner showing what rustlings is busy with.
+/// In plain mode the message is printed once instead.
+pub fn spinner(message: String) -> ProgressBar {
+    if output().plain {
+        println!("{message}");
+        return ProgressBar::hidden();
+    }
+    let progress_bar = ProgressBar::new_spinner();
+    progress_bar.set_message(message);
+    progress_bar.enable_steady_tick(100);
+    progress_bar
+}
+
+/// A bar showing how many of the exercises are done.
+/// In plain mode the progress is printed once instead.
+pub fn progress_bar(label: &str, done: usize, total: usize) -> ProgressBar {
+    if output().plain {
+        println!("{label}: {done}/{total}");
+        return ProgressBar::hidden();
+    }
+    let colors = if output().high_contrast {
+        "white.bold/black"
+    } else {
+        "green/red"
+    };
+    let bar = ProgressBar::new(total as u64);
+    bar.set_style(
+        ProgressStyle::default_bar()
+            .template(&format!(
+                "{label}: [{{bar:60.{colors}}}] {{pos}}/{{len}} {{msg}}"
+            ))
+            .progress_chars("#>-"),
+    );
+    bar.set_position(done as u64);
+    bar
+}
+
+/// Clear the terminal with an ANSI escape code, unless in plain mode or
+/// when the output isn't a terminal. Works in UNIX and newer Windows terminals.
+pub fn clear_screen() {
+    if !output().plain && console::Term::stdout().is_term() {
+        println!("\x1Bc");
+    }
+}
+
+macro_rules! warn {
+    ($msg:expr) => {{
+        use console::style;
+        let formatstr = $msg;
+        let icon = $crate::ui::emoji("⚠️ ", "!");
+        if $crate::ui::output().high_contrast {
             println!(
                 "{} {}",
-                style(Emoji("⚠️ ", "!")).red(),
-                style(formatstr).red()
+                style(icon).white().on_red().bold(),
+                style(formatstr).white().on_red().bold()
             );
+        } else {
+            println!("{} {}", style(icon).red(), style(formatstr).red());
         }
     }};
 }
 
 macro_rules! success {
-    ($fmt:literal, $ex:expr) => {{
-        use console::{style, Emoji};
-        use std::env;
-        let formatstr = format!($fmt, $ex);
-        if env::var("NO_EMOJI").is_ok() {
-            println!("{} {}", style("✓").green(), style(formatstr).green());
-        } else {
+    ($msg:expr) => {{
+        use console::style;
+        let formatstr = $msg;
+        let icon = $crate::ui::emoji("✅", "✓");
+        if $crate::ui::output().high_contrast {
             println!(
                 "{} {}",
-                style(Emoji("✅", "✓")).green(),
-                style(formatstr).green()
+                style(icon).black().on_green().bold(),
+                style(formatstr).black().on_green().bold()
             );
+        } else {
+            println!("{} {}", style(icon).green(), style(formatstr).green());
         }
     }};
 }
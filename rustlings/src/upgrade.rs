@@ -0,0 +1,326 @@
+use crate::exercise::{source_state, Exercise, ExerciseList, State, PRISTINE_DIR};
+use crate::pack;
+use crate::stats;
+use crate::variant;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Where the new exercise set is unpacked and prepared before it replaces the current one
+const STAGING_DIR: &str = ".rustlings/upgrade";
+// Where the replaced files are kept until the upgrade is complete
+const BACKUP_DIR: &str = ".rustlings/upgrade-backup";
+
+/// How an exercise of the new set relates to the current set, matched by id
+#[derive(Debug)]
+pub enum Change<'a> {
+    Added,
+    /// Same version, so the learner's work carries over
+    Unchanged(&'a Exercise),
+    /// A new version that has to be done again
+    Changed(&'a Exercise),
+}
+
+/// What the upgrade did to a single exercise, for the learner to review
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Added(String),
+    Kept(String),
+    Renamed {
+        from: String,
+        to: String,
+    },
+    /// The learner's modified version is backed up, if there was one
+    Changed {
+        name: String,
+        was_done: bool,
+        backup: Option<PathBuf>,
+    },
+    /// Exercises that aren't part of the new set, whose files are left alone
+    Removed(String),
+}
+
+/// Match every exercise of the new set with its counterpart in the current set
+pub fn plan<'a>(current: &'a [Exercise], new: &'a [Exercise]) -> Vec<(&'a Exercise, Change<'a>)> {
+    new.iter()
+        .map(|exercise| {
+            let change = match current.iter().find(|e| e.id() == exercise.id()) {
+                None => Change::Added,
+                Some(previous) if previous.version == exercise.version => {
+                    Change::Unchanged(previous)
+                }
+                Some(previous) => Change::Changed(previous),
+            };
+            (exercise, change)
+        })
+        .collect()
+}
+
+// The learner's copy of an exercise from before the upgrade
+struct LearnerCopy {
+    source: String,
+    modified: bool,
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, contents).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn rename(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::rename(from, to).map_err(|e| format!("{}: {e}", from.display()))
+}
+
+// The paths of the files in the directory, relative to it
+fn files(dir: &Path, relative: &Path, found: &mut Vec<PathBuf>) -> Result<(), String> {
+    for entry in fs::read_dir(dir.join(relative)).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = relative.join(entry.file_name());
+        if entry.path().is_dir() {
+            files(dir, &path, found)?;
+        } else {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+// A file of the workspace that the swap replaced, added or removed
+struct Swapped {
+    path: PathBuf,
+    backed_up: bool,
+    placed: bool,
+}
+
+// Move the files of the staging directory into the workspace, and the removed
+// files out of it. The files they replace are moved aside first, and moved
+// back if anything fails, so that the workspace is never half upgraded.
+fn swap(workspace: &Path, staging: &Path, removed: &[PathBuf]) -> Result<(), String> {
+    let backup = workspace.join(BACKUP_DIR);
+    let _ = fs::remove_dir_all(&backup);
+    let mut staged = Vec::new();
+    files(staging, Path::new(""), &mut staged)?;
+    staged.sort();
+
+    let mut swapped = Vec::new();
+    let mut result = Ok(());
+    for (path, place) in staged
+        .iter()
+        .map(|path| (path, true))
+        .chain(removed.iter().map(|path| (path, false)))
+    {
+        let target = workspace.join(path);
+        let mut entry = Swapped {
+            path: path.clone(),
+            backed_up: false,
+            placed: false,
+        };
+        if target.exists() {
+            result = rename(&target, &backup.join(path));
+            entry.backed_up = result.is_ok();
+        }
+        if result.is_ok() && place {
+            result = rename(&staging.join(path), &target);
+            entry.placed = result.is_ok();
+        }
+        swapped.push(entry);
+        if result.is_err() {
+            break;
+        }
+    }
+
+    if result.is_err() {
+        for entry in swapped.iter().rev() {
+            let target = workspace.join(&entry.path);
+            if entry.placed {
+                let _ = fs::remove_file(&target);
+            }
+            if entry.backed_up {
+                let _ = fs::rename(backup.join(&entry.path), &target);
+            }
+        }
+    }
+    let _ = fs::remove_dir_all(&backup);
+    result
+}
+
+/// Replace the current exercise set with the one in the archive. Learners keep
+/// their files of unchanged exercises, even when they were renamed, and their
+/// modified files of changed exercises are backed up next to the new version.
+/// Nothing is changed if the upgrade fails.
+pub fn upgrade(current: &[Exercise], archive: &Path) -> Result<Vec<Outcome>, String> {
+    let staging = Path::new(STAGING_DIR);
+    let _ = fs::remove_dir_all(staging);
+    pack::unpack(archive, staging, false, false)?;
+    let result = apply(current, staging);
+    let _ = fs::remove_dir_all(staging);
+    result
+}
+
+fn apply(current: &[Exercise], staging: &Path) -> Result<Vec<Outcome>, String> {
+    let info = fs::read_to_string(staging.join("info.toml")).map_err(|e| e.to_string())?;
+    let ExerciseList { exercises, .. } = ExerciseList::parse(&info).map_err(|e| e.to_string())?;
+
+    // The new files are prepared in the staging directory, replacing the shipped
+    // ones, and only swapped into the workspace once they are all written
+    let copies: HashMap<&str, LearnerCopy> = current
+        .iter()
+        .filter_map(|exercise| {
+            let source = fs::read_to_string(&exercise.path).ok()?;
+            let modified = exercise.pristine_source().as_ref() != Some(&source);
+            Some((exercise.id(), LearnerCopy { source, modified }))
+        })
+        .collect();
+
+    let mut outcomes = Vec::new();
+    let mut renames = Vec::new();
+    for (exercise, change) in plan(current, &exercises) {
+        let shipped = match exercise.template {
+            Some(_) => variant::render(exercise, staging, variant::seed(Path::new("")))?,
+            None => fs::read_to_string(staging.join(&exercise.path))
+                .map_err(|e| format!("{}: {e}", exercise.path.display()))?,
+        };
+        let mut source = &shipped;
+        match change {
+            Change::Added => outcomes.push(Outcome::Added(exercise.name.clone())),
+            Change::Unchanged(previous) => {
+                if let Some(copy) = copies.get(previous.id()) {
+                    source = &copy.source;
+                }
+                outcomes.push(if previous.name == exercise.name {
+                    Outcome::Kept(exercise.name.clone())
+                } else {
+                    Outcome::Renamed {
+                        from: previous.name.clone(),
+                        to: exercise.name.clone(),
+                    }
+                });
+            }
+            Change::Changed(previous) => {
+                let copy = copies.get(previous.id());
+                let backup = match copy.filter(|copy| copy.modified) {
+                    Some(copy) => {
+                        let backup = exercise.path.with_extension("previous.rs");
+                        write(&staging.join(&backup), &copy.source)?;
+                        Some(backup)
+                    }
+                    None => None,
+                };
+                outcomes.push(Outcome::Changed {
+                    name: exercise.name.clone(),
+                    was_done: copy.is_some_and(|copy| source_state(&copy.source) == State::Done),
+                    backup,
+                });
+            }
+        }
+        if let Change::Unchanged(previous) | Change::Changed(previous) = change {
+            if previous.name != exercise.name {
+                renames.push((previous.name.as_str(), exercise.name.as_str()));
+            }
+        }
+        write(&staging.join(&exercise.path), source)?;
+        write(&staging.join(PRISTINE_DIR).join(&exercise.path), &shipped)?;
+    }
+
+    // Files of renamed exercises have moved, while those of removed exercises stay
+    let new_paths: HashSet<PathBuf> = exercises.iter().map(|e| e.path.clone()).collect();
+    let mut moved = Vec::new();
+    for previous in current {
+        if !exercises.iter().any(|e| e.id() == previous.id()) {
+            outcomes.push(Outcome::Removed(previous.name.clone()));
+        } else if !new_paths.contains(&previous.path) {
+            moved.push(previous.path.clone());
+            moved.push(Path::new(PRISTINE_DIR).join(&previous.path));
+        }
+    }
+
+    swap(Path::new(""), staging, &moved)?;
+    // The exercises are upgraded by now, so this can't fail the upgrade anymore
+    if let Err(error) = stats::rename(&renames) {
+        warn!(t!("upgrade_stats_failed", error = error));
+    }
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn exercise(name: &str, id: Option<&str>, version: u32) -> Exercise {
+        Exercise {
+            name: name.to_string(),
+            id: id.map(str::to_string),
+            version,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_matches_ids() {
+        let current = [
+            exercise("intro1", None, 0),
+            exercise("vars1", None, 0),
+            exercise("dropped", None, 0),
+        ];
+        let new = [
+            exercise("intro1", None, 0),
+            exercise("variables1", Some("vars1"), 1),
+            exercise("intro2", None, 0),
+        ];
+        let changes: Vec<String> = plan(&current, &new)
+            .iter()
+            .map(|(exercise, change)| match change {
+                Change::Added => format!("+{}", exercise.name),
+                Change::Unchanged(previous) => format!("{}={}", previous.name, exercise.name),
+                Change::Changed(previous) => format!("{}~{}", previous.name, exercise.name),
+            })
+            .collect();
+        assert_eq!(changes, ["intro1=intro1", "vars1~variables1", "+intro2"]);
+    }
+
+    #[test]
+    fn test_swap_rolls_back() {
+        let workspace = std::env::temp_dir().join(format!("rustlings_swap_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&workspace);
+        let staging = workspace.join(STAGING_DIR);
+        write(&workspace.join("first.rs"), "old").unwrap();
+        write(&workspace.join("moved.rs"), "moved").unwrap();
+        write(&staging.join("first.rs"), "new").unwrap();
+        write(&staging.join("second.rs"), "new").unwrap();
+        // Can't be moved into place, as `third` is a file
+        write(&workspace.join("third"), "").unwrap();
+        write(&staging.join("third/third.rs"), "new").unwrap();
+
+        assert!(swap(&workspace, &staging, &[PathBuf::from("moved.rs")]).is_err());
+        assert_eq!(
+            fs::read_to_string(workspace.join("first.rs")).unwrap(),
+            "old"
+        );
+        assert_eq!(
+            fs::read_to_string(workspace.join("moved.rs")).unwrap(),
+            "moved"
+        );
+        assert!(!workspace.join("second.rs").exists());
+        assert!(!workspace.join(BACKUP_DIR).exists());
+
+        fs::remove_file(workspace.join("third")).unwrap();
+        write(&staging.join("first.rs"), "new").unwrap();
+        write(&staging.join("second.rs"), "new").unwrap();
+        assert!(swap(&workspace, &staging, &[PathBuf::from("moved.rs")]).is_ok());
+        assert_eq!(
+            fs::read_to_string(workspace.join("first.rs")).unwrap(),
+            "new"
+        );
+        assert_eq!(
+            fs::read_to_string(workspace.join("third/third.rs")).unwrap(),
+            "new"
+        );
+        assert!(!workspace.join("moved.rs").exists());
+        fs::remove_dir_all(workspace).unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+use crate::exercise::{Exercise, Param, PRISTINE_DIR};
+use regex::{Captures, Regex};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The seed of the learner's variants, relative to the workspace
+const SEED_PATH: &str = ".rustlings/seed";
+const PLACEHOLDER_REGEX: &str = r"\{\{\s*(\w+)\s*\}\}";
+
+/// The learner's seed, picked at random on first use
+pub fn seed(dir: &Path) -> u64 {
+    let path = dir.join(SEED_PATH);
+    if let Some(seed) = fs::read_to_string(&path)
+        .ok()
+        .and_then(|seed| seed.trim().parse().ok())
+    {
+        return seed;
+    }
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_nanos() as u64)
+        ^ u64::from(std::process::id());
+    // Without a saved seed the variants change, but only for exercises not generated yet
+    let _ = set_seed(dir, seed);
+    seed
+}
+
+// Save the seed, so that the variants stay the same
+fn set_seed(dir: &Path, seed: u64) -> io::Result<()> {
+    let path = dir.join(SEED_PATH);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, format!("{seed}\n"))
+}
+
+// A number derived from the seed, the exercise and the parameter. It's the same
+// on every machine and with every version of rustlings, so that a seed always
+// stands for the same variants.
+fn derive(seed: u64, exercise: &Exercise, param: &str) -> u64 {
+    let digest = Sha256::digest(format!("{seed}/{}/{param}", exercise.id()));
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn pick(param: &Param, number: u64) -> Option<String> {
+    match param.range {
+        Some((low, high)) if low <= high => {
+            let span = high.abs_diff(low).saturating_add(1);
+            let offset = number % span;
+            Some(low.wrapping_add_unsigned(offset).to_string())
+        }
+        Some(_) => None,
+        None if param.choices.is_empty() => None,
+        None => Some(param.choices[(number % param.choices.len() as u64) as usize].clone()),
+    }
+}
+
+/// The value of every parameter of the exercise for the seed
+pub fn values(exercise: &Exercise, seed: u64) -> Result<BTreeMap<&str, String>, String> {
+    exercise
+        .params
+        .iter()
+        .map(|(name, param)| {
+            let value = pick(param, derive(seed, exercise, name))
+                .ok_or_else(|| t!("variant_invalid_param", exercise = exercise, param = name))?;
+            Ok((name.as_str(), value))
+        })
+        .collect()
+}
+
+/// Replace the placeholders of the template, like `{{ LIMIT }}`, with the
+/// values of the parameters for the seed
+pub fn instantiate(exercise: &Exercise, template: &str, seed: u64) -> Result<String, String> {
+    let values = values(exercise, seed)?;
+    let re = Regex::new(PLACEHOLDER_REGEX).unwrap();
+    let mut unknown = None;
+    let source = re.replace_all(template, |captures: &Captures| {
+        let name = &captures[1];
+        match values.get(name) {
+            Some(value) => value.clone(),
+            None => {
+                unknown.get_or_insert_with(|| name.to_string());
+                captures[0].to_string()
+            }
+        }
+    });
+    match unknown {
+        Some(param) => Err(t!(
+            "variant_unknown_param",
+            exercise = exercise,
+            param = param
+        )),
+        None => Ok(source.into_owned()),
+    }
+}
+
+/// The variant of the exercise for the seed, from its template in the directory
+pub fn render(exercise: &Exercise, dir: &Path, seed: u64) -> Result<String, String> {
+    let Some(template) = &exercise.template else {
+        return fs::read_to_string(dir.join(&exercise.path)).map_err(|e| e.to_string());
+    };
+    let template = fs::read_to_string(dir.join(template))
+        .map_err(|e| format!("{}: {e}", template.display()))?;
+    instantiate(exercise, &template, seed)
+}
+
+/// The names of the templated exercises in the directory that weren't generated yet
+pub fn ungenerated<'a>(exercises: &'a [Exercise], dir: &Path) -> Vec<&'a str> {
+    exercises
+        .iter()
+        .filter(|e| e.template.is_some() && !dir.join(&e.path).exists())
+        .map(|e| e.name.as_str())
+        .collect()
+}
+
+/// Write the variants of the templated exercises in the directory that weren't
+/// generated yet, and store them as pristine copies for `reset` and `verify --strict`.
+/// With `regenerate`, the variants that the learner didn't modify are generated
+/// again too, and the names of the modified ones that were kept are returned.
+pub fn generate<'a>(
+    exercises: &'a [Exercise],
+    dir: &Path,
+    regenerate: bool,
+) -> Result<Vec<&'a str>, String> {
+    let templated: Vec<&Exercise> = exercises.iter().filter(|e| e.template.is_some()).collect();
+    if templated.is_empty() {
+        return Ok(Vec::new());
+    }
+    let seed = seed(dir);
+    let mut kept = Vec::new();
+    for exercise in templated {
+        let path = dir.join(&exercise.path);
+        let pristine_path = dir.join(PRISTINE_DIR).join(&exercise.path);
+        let source = fs::read_to_string(&path).ok();
+        let pristine = fs::read_to_string(&pristine_path).ok();
+        let generated = source.is_some() && pristine.is_some();
+        if generated && !regenerate {
+            continue;
+        }
+        // A file without a pristine copy is never overwritten, as it may be the learner's work
+        if source.is_some() && source != pristine {
+            kept.push(exercise.name.as_str());
+            continue;
+        }
+        let variant = render(exercise, dir, seed)?;
+        for path in [&path, &pristine_path] {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::write(path, &variant).map_err(|e| format!("{}: {e}", path.display()))?;
+        }
+    }
+    Ok(kept)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn exercise() -> Exercise {
+        let mut params = BTreeMap::new();
+        params.insert(
+            "LIMIT".to_string(),
+            Param {
+                range: Some((10, 20)),
+                ..Default::default()
+            },
+        );
+        params.insert(
+            "TYPE".to_string(),
+            Param {
+                choices: vec!["u32".to_string(), "i64".to_string()],
+                ..Default::default()
+            },
+        );
+        Exercise {
+            name: "variant".to_string(),
+            params,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_instantiate() {
+        let template = "const LIMIT: {{TYPE}} = {{ LIMIT }};\n";
+        let source = instantiate(&exercise(), template, 42).unwrap();
+        assert_eq!(source, instantiate(&exercise(), template, 42).unwrap());
+        let re = Regex::new(r"^const LIMIT: (u32|i64) = (1\d|20);\n$").unwrap();
+        assert!(re.is_match(&source), "{source}");
+
+        let sources: Vec<String> = (0..20)
+            .map(|seed| instantiate(&exercise(), template, seed).unwrap())
+            .collect();
+        assert!(sources.iter().any(|other| *other != source));
+    }
+
+    #[test]
+    fn test_unknown_placeholder() {
+        assert!(instantiate(&exercise(), "{{ COUNT }}", 42).is_err());
+    }
+}
@@ -1,7 +1,17 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
+use crate::bench;
+use crate::exercise::{CompileFailError, CompiledExercise, Exercise, Mode, State};
+use crate::protected;
+use crate::stats::{self, Event};
+use crate::toolchain;
+use crate::ui;
 use console::style;
-use indicatif::{ProgressBar, ProgressStyle};
-use std::env;
+use indicatif::ProgressBar;
+use regex::Regex;
+use std::fs;
+
+const TEST_ATTRIBUTE_REGEX: &str = r"(?m)^\s*#\[test\]";
+// The fences of the code blocks in doc comments, each example having two
+const DOCTEST_FENCE_REGEX: &str = r"(?m)^\s*//[/!]\s*```";
 
 // Verify that the provided container of Exercise objects
 // can be compiled and run without any failures.
@@ -14,21 +24,24 @@ pub fn verify<'a>(
     verbose: bool,
 ) -> Result<(), &'a Exercise> {
     let (num_done, total) = progress;
-    let bar = ProgressBar::new(total as u64);
-    bar.set_style(ProgressStyle::default_bar()
-        .template("Progress: [{bar:60.green/red}] {pos}/{len} {msg}")
-        .progress_chars("#>-")
-    );
-    bar.set_position(num_done as u64);
+    let bar = ui::progress_bar(&t!("progress"), num_done, total);
     for exercise in exercises {
-        let compile_result = match exercise.mode {
-            Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose),
-            Mode::Compile => compile_and_run_interactively(exercise),
-            Mode::Clippy => compile_only(exercise),
-        };
+        let compile_result = check_toolchain(exercise)
+            .and_then(|_| check_protected(exercise))
+            .and_then(|_| match exercise.mode {
+                Mode::Test | Mode::Unsafe | Mode::Doctest => {
+                    compile_and_test(exercise, RunMode::Interactive, verbose)
+                }
+                Mode::Compile => compile_and_run_interactively(exercise),
+                Mode::Clippy => compile_only(exercise),
+                Mode::Bench => compile_and_bench(exercise, RunMode::Interactive),
+                Mode::CompileFail => compile_and_expect_error(exercise, RunMode::Interactive),
+            });
         if !compile_result.unwrap_or(false) {
+            stats::record(exercise, Event::Started);
             return Err(exercise);
         }
+        stats::record(exercise, Event::Completed);
         let percentage = num_done as f32 / total as f32 * 100.0;
         bar.set_message(format!("({:.1} %)", percentage));
         bar.inc(1);
@@ -36,11 +49,308 @@ pub fn verify<'a>(
     Ok(())
 }
 
+// Counts the tests or examples of an exercise's source, to compare with the shipped version
+type Counter = fn(&str) -> usize;
+
+// Compile and test every exercise from scratch, regardless of the
+// `I AM NOT DONE` markers, and report the exercises that are marked
+// as done without passing and the exercises with deleted tests
+pub fn verify_strict(exercises: &[Exercise]) -> Result<(), ()> {
+    let mut problems = Vec::new();
+    let mut passing = 0;
+    for exercise in exercises {
+        let progress_bar = ui::spinner(t!("strict_checking", exercise = exercise));
+        let result = verify_quietly(exercise);
+        progress_bar.finish_and_clear();
+
+        if result.is_ok() {
+            passing += 1;
+        } else if exercise.looks_done() {
+            problems.push(t!("strict_done_but_failing", exercise = exercise));
+        }
+        // Exercises without a pristine copy are reported below
+        let altered = protected::altered_items(exercise).unwrap_or_default();
+        if !altered.is_empty() {
+            problems.push(t!(
+                "protected_altered",
+                exercise = exercise,
+                items = altered.join(", ")
+            ));
+        }
+        let counted: Option<(Counter, _)> = match exercise.mode {
+            Mode::Test | Mode::Bench | Mode::Unsafe => Some((count_tests, "strict_tests_removed")),
+            Mode::Doctest => Some((count_doctests, "strict_doctests_removed")),
+            _ => None,
+        };
+        if counted.is_none() && exercise.protected.is_empty() {
+            continue;
+        }
+        let Some(pristine) = exercise.pristine_source() else {
+            problems.push(t!("strict_no_pristine", exercise = exercise));
+            continue;
+        };
+        if let Some((count, key)) = counted {
+            let expected = count(&pristine);
+            let found = fs::read_to_string(&exercise.path)
+                .map(|source| count(&source))
+                .unwrap_or_default();
+            if found < expected {
+                problems.push(t!(
+                    key,
+                    exercise = exercise,
+                    found = found,
+                    expected = expected
+                ));
+            }
+        }
+    }
+
+    for problem in &problems {
+        warn!(problem.clone());
+    }
+    println!(
+        "{}",
+        t!("strict_summary", passing = passing, total = exercises.len())
+    );
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(())
+    }
+}
+
+// The number of test functions in the source of an exercise
+fn count_tests(source: &str) -> usize {
+    let re = Regex::new(TEST_ATTRIBUTE_REGEX).unwrap();
+    re.find_iter(source).count()
+}
+
+// The number of code examples in the doc comments of an exercise
+fn count_doctests(source: &str) -> usize {
+    let re = Regex::new(DOCTEST_FENCE_REGEX).unwrap();
+    re.find_iter(source).count() / 2
+}
+
+// The name and outcome (`ok`, `FAILED` or `ignored`) of every doctest
+// in the output of `rustdoc --test`, like `add (line 3)` and `ok` for
+// `test src/lib.rs - add (line 3) ... ok`
+fn doctest_results(stdout: &str) -> Vec<(&str, &str)> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (test, outcome) = line.strip_prefix("test ")?.rsplit_once(" ... ")?;
+            let name = test.split_once(" - ").map_or(test, |(_, name)| name);
+            Some((name, outcome.trim()))
+        })
+        .collect()
+}
+
+// List the doctests of the exercise with a mark for whether they passed
+fn print_doctest_results(exercise: &Exercise, stdout: &str) {
+    let stdout = console::strip_ansi_codes(stdout);
+    let results = doctest_results(&stdout);
+    if results.is_empty() {
+        return;
+    }
+    println!("{}", t!("doctest_results", exercise = exercise));
+    for (name, outcome) in results {
+        let mark = match outcome {
+            "ok" => style(ui::emoji("✅", "✓")).green(),
+            "ignored" => style(ui::emoji("➖", "-")).dim(),
+            _ => style(ui::emoji("❌", "✗")).red(),
+        };
+        println!("  {mark} {name}");
+    }
+    println!();
+}
+
+// Refuse to verify the given Exercise if the toolchain is older than it requires,
+// or can't check it for undefined behavior
+pub fn check_toolchain(exercise: &Exercise) -> Result<(), ()> {
+    match toolchain_message(exercise) {
+        Some(message) => {
+            warn!(message);
+            Err(())
+        }
+        None => Ok(()),
+    }
+}
+
+fn toolchain_message(exercise: &Exercise) -> Option<String> {
+    if !exercise.can_check_undefined_behavior() {
+        return Some(t!("unsafe_no_checker", exercise = exercise));
+    }
+    let required = toolchain::missing_requirement(exercise)?;
+    Some(t!(
+        "toolchain_too_old",
+        exercise = exercise,
+        required = required,
+        installed = toolchain::installed().map_or("?".to_string(), |v| v.to_string())
+    ))
+}
+
+// Refuse to verify the given Exercise if the learner altered its protected items,
+// and warn when they can't be checked
+pub fn check_protected(exercise: &Exercise) -> Result<(), ()> {
+    let Some(altered) = protected::altered_items(exercise) else {
+        warn!(t!("protected_unchecked", exercise = exercise));
+        return Ok(());
+    };
+    if altered.is_empty() {
+        return Ok(());
+    }
+    warn!(t!(
+        "protected_altered",
+        exercise = exercise,
+        items = altered.join(", ")
+    ));
+    println!("{}", t!("protected_restore", name = exercise.name));
+    Err(())
+}
+
+// Compile and run the given Exercise without printing anything,
+// returning the compiler or test output on failure
+pub fn verify_quietly(exercise: &Exercise) -> Result<(), String> {
+    if let Some(message) = toolchain_message(exercise) {
+        return Err(message);
+    }
+    if exercise.mode == Mode::CompileFail {
+        let code = exercise.error_code.as_deref().unwrap_or("error");
+        return match exercise.compile_expecting_error() {
+            Ok(()) => Ok(()),
+            Err(CompileFailError::WrongError(output)) => Err(output.stderr),
+            Err(CompileFailError::Compiled) => Err(t!(
+                "compile_fail_compiled",
+                exercise = exercise,
+                code = code
+            )),
+        };
+    }
+    let compiled = exercise.compile().map_err(|output| output.stderr)?;
+    let output = match exercise.mode {
+        Mode::Clippy => return Ok(()),
+        _ => compiled
+            .run()
+            .map_err(|output| format!("{}{}", output.stdout, output.stderr))?,
+    };
+    let max_nanos = match (exercise.mode, &exercise.bench) {
+        (Mode::Bench, Some(benchmark)) => benchmark.max_nanos,
+        (Mode::Bench, None) => return Err(t!("bench_missing", exercise = exercise)),
+        _ => return Ok(()),
+    };
+    match bench::timing(&output.stdout) {
+        Some(timing) if !timing.exceeds(max_nanos) => Ok(()),
+        _ => Err(t!(
+            "bench_too_slow",
+            exercise = exercise,
+            max = bench::format_nanos(max_nanos as f64)
+        )),
+    }
+}
+
 enum RunMode {
     Interactive,
     NonInteractive,
 }
 
+// Compile the given Exercise, expecting the compiler to reject it with its error code
+fn compile_and_expect_error(exercise: &Exercise, run_mode: RunMode) -> Result<bool, ()> {
+    let progress_bar = ui::spinner(t!("compiling", exercise = exercise));
+    let result = exercise.compile_expecting_error();
+    progress_bar.finish_and_clear();
+
+    let code = exercise.error_code.as_deref().unwrap_or("error");
+    match result {
+        Ok(()) => match run_mode {
+            RunMode::Interactive => Ok(prompt_for_completion(exercise, None)),
+            RunMode::NonInteractive => {
+                success!(t!(
+                    "compile_failed_as_expected",
+                    exercise = exercise,
+                    code = code
+                ));
+                Ok(true)
+            }
+        },
+        Err(CompileFailError::WrongError(output)) => {
+            stats::record(exercise, Event::TestFailed);
+            warn!(t!(
+                "compile_fail_wrong_error",
+                exercise = exercise,
+                code = code
+            ));
+            println!("{}", output.stderr);
+            Err(())
+        }
+        Err(CompileFailError::Compiled) => {
+            stats::record(exercise, Event::TestFailed);
+            warn!(t!(
+                "compile_fail_compiled",
+                exercise = exercise,
+                code = code
+            ));
+            Err(())
+        }
+    }
+}
+
+// Check that the compiler rejects the given Exercise as expected
+pub fn compile_fail(exercise: &Exercise) -> Result<(), ()> {
+    compile_and_expect_error(exercise, RunMode::NonInteractive)?;
+    Ok(())
+}
+
+// Compile the given Exercise with optimizations, run its tests and time it.
+// It only passes when it's faster than the threshold of its benchmark.
+fn compile_and_bench(exercise: &Exercise, run_mode: RunMode) -> Result<bool, ()> {
+    let Some(benchmark) = &exercise.bench else {
+        warn!(t!("bench_missing", exercise = exercise));
+        return Err(());
+    };
+    let progress_bar = ui::spinner(t!("benchmarking", exercise = exercise));
+
+    let compilation = compile(exercise, &progress_bar)?;
+    let result = compilation.run();
+    let timing = match result.as_ref().map(|output| bench::timing(&output.stdout)) {
+        Ok(Some(timing)) => timing,
+        _ => {
+            progress_bar.finish_and_clear();
+            stats::record(exercise, Event::TestFailed);
+            warn!(t!("testing_failed", exercise = exercise));
+            let output = result.unwrap_or_else(|output| output);
+            println!("{}", output.stdout);
+            return Err(());
+        }
+    };
+    let description = bench::describe(timing, bench::reference_timing(exercise));
+    progress_bar.finish_and_clear();
+
+    if timing.exceeds(benchmark.max_nanos) {
+        stats::record(exercise, Event::TestFailed);
+        warn!(t!(
+            "bench_too_slow",
+            exercise = exercise,
+            max = bench::format_nanos(benchmark.max_nanos as f64)
+        ));
+        println!("{description}");
+        return Err(());
+    }
+    match run_mode {
+        RunMode::Interactive => Ok(prompt_for_completion(exercise, Some(description))),
+        RunMode::NonInteractive => {
+            success!(t!("successfully_benchmarked", exercise = exercise));
+            println!("{description}");
+            Ok(true)
+        }
+    }
+}
+
+// Time the given Exercise, failing when it's too slow
+pub fn bench(exercise: &Exercise) -> Result<(), ()> {
+    compile_and_bench(exercise, RunMode::NonInteractive)?;
+    Ok(())
+}
+
 // Compile and run the resulting test harness of the given Exercise
 pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
     compile_and_test(exercise, RunMode::NonInteractive, verbose)?;
@@ -49,9 +359,7 @@ pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
 
 // Invoke the rust compiler without running the resulting binary
 fn compile_only(exercise: &Exercise) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+    let progress_bar = ui::spinner(t!("compiling", exercise = exercise));
 
     let _ = compile(exercise, &progress_bar)?;
     progress_bar.finish_and_clear();
@@ -61,20 +369,18 @@ fn compile_only(exercise: &Exercise) -> Result<bool, ()> {
 
 // Compile the given Exercise and run the resulting binary in an interactive mode
 fn compile_and_run_interactively(exercise: &Exercise) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+    let progress_bar = ui::spinner(t!("compiling", exercise = exercise));
 
     let compilation = compile(exercise, &progress_bar)?;
 
-    progress_bar.set_message(format!("Running {exercise}..."));
+    progress_bar.set_message(t!("running", exercise = exercise));
     let result = compilation.run();
     progress_bar.finish_and_clear();
 
     let output = match result {
         Ok(output) => output,
         Err(output) => {
-            warn!("Ran {} with errors", exercise);
+            warn!(t!("ran_with_errors", exercise = exercise));
             println!("{}", output.stdout);
             println!("{}", output.stderr);
             return Err(());
@@ -84,12 +390,15 @@ fn compile_and_run_interactively(exercise: &Exercise) -> Result<bool, ()> {
     Ok(prompt_for_completion(exercise, Some(output.stdout)))
 }
 
+// Whether Miri or AddressSanitizer reported undefined behavior
+fn found_undefined_behavior(stderr: &str) -> bool {
+    stderr.contains("Undefined Behavior") || stderr.contains("ERROR: AddressSanitizer")
+}
+
 // Compile the given Exercise as a test harness and display
 // the output if verbose is set to true
 fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Testing {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+    let progress_bar = ui::spinner(t!("testing", exercise = exercise));
 
     let compilation = compile(exercise, &progress_bar)?;
     let result = compilation.run();
@@ -99,6 +408,8 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool) -> Re
         Ok(output) => {
             if verbose {
                 println!("{}", output.stdout);
+            } else if exercise.mode == Mode::Doctest {
+                print_doctest_results(exercise, &output.stdout);
             }
             if let RunMode::Interactive = run_mode {
                 Ok(prompt_for_completion(exercise, None))
@@ -106,11 +417,28 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool) -> Re
                 Ok(true)
             }
         }
+        Err(output) if exercise.mode == Mode::Unsafe => {
+            stats::record(exercise, Event::TestFailed);
+            if found_undefined_behavior(&output.stderr) {
+                warn!(t!("undefined_behavior", exercise = exercise));
+            } else {
+                warn!(t!("testing_failed", exercise = exercise));
+            }
+            println!("{}", output.stdout);
+            println!("{}", output.stderr);
+            Err(())
+        }
+        Err(output) if exercise.mode == Mode::Doctest => {
+            stats::record(exercise, Event::TestFailed);
+            warn!(t!("testing_failed", exercise = exercise));
+            print_doctest_results(exercise, &output.stdout);
+            println!("{}", output.stdout);
+            println!("{}", output.stderr);
+            Err(())
+        }
         Err(output) => {
-            warn!(
-                "Testing of {} failed! Please try again. Here's the output:",
-                exercise
-            );
+            stats::record(exercise, Event::TestFailed);
+            warn!(t!("testing_failed", exercise = exercise));
             println!("{}", output.stdout);
             Err(())
         }
@@ -119,9 +447,9 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool) -> Re
 
 // Compile the given Exercise and return an object with information
 // about the state of the compilation
-fn compile<'a, 'b>(
+fn compile<'a>(
     exercise: &'a Exercise,
-    progress_bar: &'b ProgressBar,
+    progress_bar: &ProgressBar,
 ) -> Result<CompiledExercise<'a>, ()> {
     let compilation_result = exercise.compile();
 
@@ -129,10 +457,8 @@ fn compile<'a, 'b>(
         Ok(compilation) => Ok(compilation),
         Err(output) => {
             progress_bar.finish_and_clear();
-            warn!(
-                "Compiling of {} failed! Please try again. Here's the output:",
-                exercise
-            );
+            stats::record(exercise, Event::CompileFailed);
+            warn!(t!("compiling_failed", exercise = exercise));
             println!("{}", output.stderr);
             Err(())
         }
@@ -146,23 +472,35 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) ->
     };
 
     match exercise.mode {
-        Mode::Compile => success!("Successfully ran {}!", exercise),
-        Mode::Test => success!("Successfully tested {}!", exercise),
-        Mode::Clippy => success!("Successfully compiled {}!", exercise),
+        Mode::Compile => success!(t!("successfully_ran", exercise = exercise)),
+        Mode::Test | Mode::Unsafe | Mode::Doctest => {
+            success!(t!("successfully_tested", exercise = exercise))
+        }
+        Mode::Clippy => success!(t!("successfully_compiled", exercise = exercise)),
+        Mode::Bench => success!(t!("successfully_benchmarked", exercise = exercise)),
+        Mode::CompileFail => success!(t!(
+            "compile_failed_as_expected",
+            exercise = exercise,
+            code = exercise.error_code.as_deref().unwrap_or("error")
+        )),
     }
 
-    let no_emoji = env::var("NO_EMOJI").is_ok();
+    let no_emoji = !ui::output().emoji;
 
-    let clippy_success_msg = if no_emoji {
-        "The code is compiling, and Clippy is happy!"
+    let clippy = if no_emoji {
+        "Clippy"
     } else {
-        "The code is compiling, and 📎 Clippy 📎 is happy!"
+        "📎 Clippy 📎"
     };
 
     let success_msg = match exercise.mode {
-        Mode::Compile => "The code is compiling!",
-        Mode::Test => "The code is compiling, and the tests pass!",
-        Mode::Clippy => clippy_success_msg,
+        Mode::Compile => t!("code_compiling"),
+        Mode::Test => t!("tests_pass"),
+        Mode::Clippy => t!("clippy_happy", clippy = clippy),
+        Mode::Unsafe => t!("no_undefined_behavior"),
+        Mode::Doctest => t!("doctests_pass"),
+        Mode::Bench => t!("bench_fast_enough"),
+        Mode::CompileFail => t!("compile_fail_expected"),
     };
 
     println!();
@@ -174,17 +512,16 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) ->
     println!();
 
     if let Some(output) = prompt_output {
-        println!("Output:");
+        println!("{}", t!("output"));
         println!("{}", separator());
         println!("{output}");
         println!("{}", separator());
         println!();
     }
 
-    println!("You can keep working on this exercise,");
     println!(
-        "or jump into the next one by removing the {} comment:",
-        style("`I AM NOT DONE`").bold()
+        "{}",
+        t!("keep_working", marker = style("`I AM NOT DONE`").bold())
     );
     println!();
     for context_line in context {
@@ -208,3 +545,32 @@ fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>) ->
 fn separator() -> console::StyledObject<&'static str> {
     style("====================").bold()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_count_tests() {
+        let source = "#[test]\nfn a() {}\n\n    #[test]\n    fn b() {}\n// #[test]\n";
+        assert_eq!(count_tests(source), 2);
+    }
+
+    #[test]
+    fn test_count_doctests() {
+        let source = "/// ```\n/// assert!(true);\n/// ```\n//! ```rust\n//! ```\n// ```\n";
+        assert_eq!(count_doctests(source), 2);
+    }
+
+    #[test]
+    fn test_doctest_results() {
+        let stdout = "running 2 tests\n\
+            test exercises/doc.rs - add (line 3) ... ok\n\
+            test exercises/doc.rs - Stack::push (line 12) ... FAILED\n\n\
+            test result: FAILED. 1 passed; 1 failed";
+        assert_eq!(
+            doctest_results(stdout),
+            [("add (line 3)", "ok"), ("Stack::push (line 12)", "FAILED")]
+        );
+    }
+}
@@ -0,0 +1,13 @@
+fn sum(n: u64) -> u64 {
+    (1..=n).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_up() {
+        assert_eq!(sum(4), 10);
+    }
+}
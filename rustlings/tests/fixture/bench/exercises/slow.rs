@@ -0,0 +1,19 @@
+fn sum(n: u64) -> u64 {
+    let mut total = 0;
+    for i in 1..=n {
+        for _ in 0..i {
+            total += std::hint::black_box(1);
+        }
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_up() {
+        assert_eq!(sum(4), 10);
+    }
+}
@@ -0,0 +1,13 @@
+fn sum(n: u64) -> u64 {
+    n * (n + 1) / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_up() {
+        assert_eq!(sum(4), 10);
+    }
+}
@@ -0,0 +1,8 @@
+/// Adds two numbers.
+///
+/// ```
+/// assert_eq!(documented::add(2, 3), 5);
+/// ```
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
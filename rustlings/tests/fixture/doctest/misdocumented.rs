@@ -0,0 +1,17 @@
+/// Adds two numbers.
+///
+/// ```
+/// assert_eq!(misdocumented::add(2, 3), 5);
+/// ```
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+/// Subtracts the second number from the first.
+///
+/// ```
+/// assert_eq!(misdocumented::sub(5, 3), 2);
+/// ```
+pub fn sub(a: i32, b: i32) -> i32 {
+    b - a
+}
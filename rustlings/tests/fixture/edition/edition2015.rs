@@ -0,0 +1,5 @@
+// `async` only became a keyword in the 2018 edition
+fn main() {
+    let async = 1;
+    println!("{}", async);
+}
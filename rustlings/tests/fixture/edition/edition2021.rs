@@ -0,0 +1,7 @@
+#[test]
+fn arrays_iterate_over_values() {
+    // Before the 2021 edition, this iterated over references
+    for value in [1, 2].into_iter() {
+        let _: i32 = value;
+    }
+}
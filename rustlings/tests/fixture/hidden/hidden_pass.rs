@@ -0,0 +1,8 @@
+fn double(x: i32) -> i32 {
+    x * 2
+}
+
+#[test]
+fn doubles_two() {
+    assert_eq!(double(2), 4);
+}
@@ -0,0 +1,10 @@
+mod arithmetic;
+
+fn double(x: i32) -> i32 {
+    arithmetic::twice(x)
+}
+
+#[test]
+fn doubles_two() {
+    assert_eq!(double(2), 4);
+}
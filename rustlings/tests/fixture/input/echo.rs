@@ -0,0 +1,9 @@
+use std::io::Read;
+
+fn main() {
+    // More than a pipe holds, before any of the input is read
+    println!("{}", "-".repeat(100_000));
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).unwrap();
+    println!("Read {} bytes", input.len());
+}
@@ -0,0 +1,8 @@
+use std::io::Read;
+
+fn main() {
+    let name = std::env::args().nth(1).expect("a name to greet");
+    let mut greeting = String::new();
+    std::io::stdin().read_to_string(&mut greeting).unwrap();
+    println!("{}, {}!", greeting.trim(), name);
+}
@@ -0,0 +1,15 @@
+// I AM NOT DONE
+
+fn sum_to(n: u64) -> u64 {
+    todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums() {
+        assert_eq!(sum_to(4), 10);
+    }
+}
@@ -0,0 +1,16 @@
+// I AM NOT DONE
+
+fn is_adult(age: u8) -> bool {
+    todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adults() {
+        assert!(is_adult(18));
+        assert!(!is_adult(17));
+    }
+}
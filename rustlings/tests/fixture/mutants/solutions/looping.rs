@@ -0,0 +1,19 @@
+fn sum_to(n: u64) -> u64 {
+    let mut total = 0;
+    let mut i = 1;
+    while i <= n {
+        total += i;
+        i += 1;
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums() {
+        assert_eq!(sum_to(4), 10);
+    }
+}
@@ -0,0 +1,14 @@
+fn is_adult(age: u8) -> bool {
+    age >= 18
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adults() {
+        assert!(is_adult(18));
+        assert!(!is_adult(17));
+    }
+}
@@ -0,0 +1,17 @@
+fn clamp(x: u32) -> u32 {
+    if x > 1000 {
+        1000
+    } else {
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_keeps_small_values() {
+        check(|x: u32| clamp(x) == x);
+    }
+}
@@ -0,0 +1,22 @@
+fn reverse(v: &[i32]) -> Vec<i32> {
+    v.iter().rev().copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reversing_twice() {
+        check(|v: Vec<i32>| reverse(&reverse(&v)) == v);
+    }
+
+    #[test]
+    fn first_becomes_last() {
+        check(|(v, x): (Vec<i32>, i32)| {
+            let mut w = vec![x];
+            w.extend(&v);
+            assert_eq!(reverse(&w).last(), Some(&x));
+        });
+    }
+}
@@ -0,0 +1,13 @@
+fn double(x: i32) -> i32 {
+    x + x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles() {
+        assert_eq!(double(2), double(2));
+    }
+}
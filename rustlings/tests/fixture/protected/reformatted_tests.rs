@@ -0,0 +1,12 @@
+fn double(x: i32) -> i32 {
+    x * 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Checks the doubling
+    #[test]
+    fn doubles() { assert_eq!( double(2), 4 ); }
+}
@@ -0,0 +1,23 @@
+// guarded.rs
+// Make the tests pass!
+
+// I AM NOT DONE
+
+fn double(x: i32) -> i32 {
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles() {
+        assert_eq!(double(2), 4);
+    }
+
+    #[test]
+    fn doubles_negative_numbers() {
+        assert_eq!(double(-3), -6);
+    }
+}
@@ -0,0 +1,15 @@
+// I AM NOT DONE
+
+fn double(x: u64) -> u64 {
+    todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles() {
+        assert_eq!(double(2), 4);
+    }
+}
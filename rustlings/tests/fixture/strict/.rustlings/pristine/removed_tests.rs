@@ -0,0 +1,11 @@
+// I AM NOT DONE
+
+#[test]
+fn kept() {
+    assert_eq!(1 + 1, 2);
+}
+
+#[test]
+fn removed() {
+    assert_eq!(2 + 2, 5);
+}
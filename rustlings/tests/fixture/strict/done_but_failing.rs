@@ -0,0 +1,4 @@
+#[test]
+fn failing() {
+    assert_eq!(1 + 1, 3);
+}
@@ -0,0 +1,4 @@
+#[test]
+fn kept() {
+    assert_eq!(1 + 1, 2);
+}
@@ -0,0 +1,4 @@
+#[test]
+fn passing() {
+    assert_eq!(1 + 1, 2);
+}
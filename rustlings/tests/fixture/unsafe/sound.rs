@@ -0,0 +1,10 @@
+fn third(values: &[i32]) -> i32 {
+    assert!(values.len() > 2);
+    // SAFETY: the slice has at least three elements
+    unsafe { *values.as_ptr().add(2) }
+}
+
+#[test]
+fn reads_the_third_value() {
+    assert_eq!(third(&[1, 2, 3]), 3);
+}
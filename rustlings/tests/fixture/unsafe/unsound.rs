@@ -0,0 +1,10 @@
+fn third(values: &[i32]) -> i32 {
+    // Nothing checks that the slice is long enough
+    unsafe { *values.as_ptr().add(2) }
+}
+
+#[test]
+fn reads_the_third_value() {
+    let values = vec![1, 2];
+    assert!(third(&values) != 42);
+}
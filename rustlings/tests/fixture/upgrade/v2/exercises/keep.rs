@@ -0,0 +1,5 @@
+// I AM NOT DONE
+
+fn main() {
+    // keep
+}
@@ -3,6 +3,7 @@ use glob::glob;
 use predicates::boolean::PredicateBooleanExt;
 use std::fs::File;
 use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[test]
@@ -11,6 +12,93 @@ fn runs_without_arguments() {
     cmd.assert().success();
 }
 
+#[test]
+fn runs_without_arguments_in_plain_mode() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .env("RUSTLINGS_PLAIN", "1")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("|___/").not());
+}
+
+#[test]
+fn no_color_removes_escape_codes() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .env("CLICOLOR_FORCE", "1")
+        .env("NO_COLOR", "1")
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("\x1b[").not());
+}
+
+#[test]
+fn verify_in_plain_mode_prints_progress() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .env("RUSTLINGS_PLAIN", "1")
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Progress: 0/2"));
+}
+
+#[test]
+fn output_follows_config_file() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .current_dir("tests/fixture/config")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("|___/").not());
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .env("CLICOLOR_FORCE", "1")
+        .current_dir("tests/fixture/config")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("Progress: 0/1")
+                .and(predicates::str::contains("\x1b[").not()),
+        );
+}
+
+#[test]
+fn watch_clear_only_clears_terminals() {
+    let workspace = std::env::temp_dir().join(format!("rustlings_clear_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&workspace);
+    std::fs::create_dir_all(workspace.join("exercises")).unwrap();
+    std::fs::copy(
+        "tests/fixture/watch/exercises/pending.rs",
+        workspace.join("exercises/pending.rs"),
+    )
+    .unwrap();
+    std::fs::write(
+        workspace.join("info.toml"),
+        "[[exercises]]\nname = \"pending\"\npath = \"exercises/pending.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("watch")
+        .current_dir(&workspace)
+        .with_stdin()
+        .buffer("clear\nquit\n")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("We hope you're enjoying learning about Rust!")
+                .and(predicates::str::contains("\x1b[2J").not())
+                .and(predicates::str::contains("\x1bc").not()),
+        );
+    std::fs::remove_dir_all(workspace).unwrap();
+}
+
 #[test]
 fn fails_when_in_wrong_dir() {
     Command::cargo_bin("rustlings")
@@ -44,7 +132,7 @@ fn verify_fails_if_some_fails() {
 fn run_single_compile_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compSuccess"])
+        .args(["run", "compSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
@@ -54,7 +142,7 @@ fn run_single_compile_success() {
 fn run_single_compile_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compFailure"])
+        .args(["run", "compFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -64,7 +152,7 @@ fn run_single_compile_failure() {
 fn run_single_test_success() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .success();
@@ -74,7 +162,7 @@ fn run_single_test_success() {
 fn run_single_test_failure() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testFailure"])
+        .args(["run", "testFailure"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -84,7 +172,7 @@ fn run_single_test_failure() {
 fn run_single_test_not_passed() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testNotPassed.rs"])
+        .args(["run", "testNotPassed.rs"])
         .current_dir("tests/fixture/failure/")
         .assert()
         .code(1);
@@ -104,7 +192,7 @@ fn run_single_test_no_filename() {
 fn run_single_test_no_exercise() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "compNoExercise.rs"])
+        .args(["run", "compNoExercise.rs"])
         .current_dir("tests/fixture/failure")
         .assert()
         .code(1);
@@ -114,7 +202,7 @@ fn run_single_test_no_exercise() {
 fn reset_single_exercise() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["reset", "intro1"])
+        .args(["reset", "intro1"])
         .assert()
         .code(0);
 }
@@ -135,7 +223,30 @@ fn reset_no_exercise() {
 fn get_hint_for_single_test() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["hint", "testFailure"])
+        .args(["hint", "testFailure"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(0)
+        .stdout("Hello!\n");
+}
+
+#[test]
+fn get_localized_hint_for_single_test() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--lang", "fr-FR", "hint", "testFailure"])
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(0)
+        .stdout("Bonjour !\n");
+}
+
+#[test]
+fn localized_hint_falls_back_to_english() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "testFailure"])
+        .env("RUSTLINGS_LANG", "de")
         .current_dir("tests/fixture/failure")
         .assert()
         .code(0)
@@ -171,7 +282,7 @@ fn all_exercises_require_confirmation() {
 fn run_compile_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_exercise"])
+        .args(["run", "pending_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -182,7 +293,7 @@ fn run_compile_exercise_does_not_prompt() {
 fn run_test_exercise_does_not_prompt() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "pending_test_exercise"])
+        .args(["run", "pending_test_exercise"])
         .current_dir("tests/fixture/state")
         .assert()
         .code(0)
@@ -193,7 +304,7 @@ fn run_test_exercise_does_not_prompt() {
 fn run_single_test_success_with_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["--nocapture", "run", "testSuccess"])
+        .args(["--nocapture", "run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -204,7 +315,7 @@ fn run_single_test_success_with_output() {
 fn run_single_test_success_without_output() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["run", "testSuccess"])
+        .args(["run", "testSuccess"])
         .current_dir("tests/fixture/success/")
         .assert()
         .code(0)
@@ -215,7 +326,7 @@ fn run_single_test_success_without_output() {
 fn run_rustlings_list() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/success")
         .assert()
         .success();
@@ -225,7 +336,7 @@ fn run_rustlings_list() {
 fn run_rustlings_list_no_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/success")
         .assert()
         .success()
@@ -236,7 +347,7 @@ fn run_rustlings_list_no_pending() {
 fn run_rustlings_list_both_done_and_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list"])
+        .args(["list"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
@@ -247,7 +358,7 @@ fn run_rustlings_list_both_done_and_pending() {
 fn run_rustlings_list_without_pending() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--solved"])
+        .args(["list", "--solved"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
@@ -258,9 +369,1190 @@ fn run_rustlings_list_without_pending() {
 fn run_rustlings_list_without_done() {
     Command::cargo_bin("rustlings")
         .unwrap()
-        .args(&["list", "--unsolved"])
+        .args(["list", "--unsolved"])
         .current_dir("tests/fixture/state")
         .assert()
         .success()
         .stdout(predicates::str::contains("Done").not());
 }
+
+#[test]
+fn lsp_serve_reports_pending_exercise() {
+    let messages = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///rustlings/pending_exercise.rs","text":"// I AM NOT DONE\nfn main() {}\n"}}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#,
+        r#"{"jsonrpc":"2.0","method":"exit"}"#,
+    ];
+    let input: String = messages
+        .iter()
+        .map(|m| format!("Content-Length: {}\r\n\r\n{m}", m.len()))
+        .collect();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["lsp", "--serve"])
+        .current_dir("tests/fixture/state")
+        .with_stdin()
+        .buffer(input)
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("codeActionProvider")
+                .and(predicates::str::contains("textDocument/publishDiagnostics"))
+                .and(predicates::str::contains("I AM NOT DONE")),
+        );
+}
+
+#[test]
+fn lsp_reset_restores_pristine_copy() {
+    let workspace = std::env::temp_dir().join(format!("rustlings_lsp_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&workspace);
+    let write = |path: &str, contents: &str| {
+        let path = workspace.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    };
+    write(
+        "info.toml",
+        "[[exercises]]\nname = \"add\"\npath = \"exercises/add.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    );
+    let pristine = "// I AM NOT DONE\nfn main() {}\n";
+    write("exercises/add.rs", "fn main() { oops }\n");
+    write(".rustlings/pristine/exercises/add.rs", pristine);
+    let messages = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"workspace/executeCommand","params":{"command":"rustlings.resetExercise","arguments":["add"]}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#,
+        r#"{"jsonrpc":"2.0","method":"exit"}"#,
+    ];
+    let input: String = messages
+        .iter()
+        .map(|m| format!("Content-Length: {}\r\n\r\n{m}", m.len()))
+        .collect();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["lsp", "--serve"])
+        .current_dir(&workspace)
+        .with_stdin()
+        .buffer(input)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Reset exercises/add.rs"));
+    assert_eq!(
+        std::fs::read_to_string(workspace.join("exercises/add.rs")).unwrap(),
+        pristine
+    );
+    std::fs::remove_dir_all(workspace).unwrap();
+}
+
+#[test]
+fn run_single_compile_with_dependency() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "depSuccess"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Hello from a dependency"));
+}
+
+#[test]
+fn run_rustlings_list_with_locked() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--unsolved"])
+        .current_dir("tests/fixture/graph")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Locked").and(predicates::str::contains("Pending")));
+}
+
+#[test]
+fn run_rustlings_list_graph_as_dot() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--graph", "dot"])
+        .current_dir("tests/fixture/graph")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("digraph")
+                .and(predicates::str::contains(
+                    "\"finished_basics\" -> \"pending_basics\";",
+                ))
+                .and(predicates::str::contains("lhead=cluster_1")),
+        );
+}
+
+#[test]
+fn run_next_skips_locked_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "next"])
+        .current_dir("tests/fixture/graph")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("pending_basics"));
+}
+
+#[test]
+fn run_locked_exercise_warns() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "locked_advanced"])
+        .current_dir("tests/fixture/graph")
+        .assert()
+        .stdout(predicates::str::contains(
+            "locked_advanced is still locked, it builds on: pending_basics",
+        ));
+}
+
+#[test]
+fn fails_on_cyclic_requirements() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list"])
+        .current_dir("tests/fixture/cycle")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("chicken -> egg -> chicken"));
+}
+
+#[test]
+fn run_rustlings_list_by_topic() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--by-topic"])
+        .current_dir("tests/fixture/topics")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("Mastered")
+                .and(predicates::str::contains("Quiz pending"))
+                .and(predicates::str::contains("In progress"))
+                .and(predicates::str::contains("You mastered 1 / 3 topics")),
+        );
+}
+
+#[test]
+fn run_rustlings_list_topic_report() {
+    let report = std::env::temp_dir().join(format!("rustlings_report_{}.md", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--report", report.to_str().unwrap()])
+        .current_dir("tests/fixture/topics")
+        .assert()
+        .success();
+    let contents = std::fs::read_to_string(&report).unwrap();
+    std::fs::remove_file(&report).unwrap();
+    assert!(contents.contains("| mastered | 1 / 1 | passing_quiz (passed) | Mastered |"));
+    assert!(contents.contains("README: README.md"));
+    assert!(contents.contains("- [ ] pending_exercise"));
+}
+
+#[test]
+fn run_rustlings_stats() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["stats"])
+        .current_dir("tests/fixture/stats")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::is_match(r"basics\s+1/2\s+3m 20s\s+5\s+0\s+1")
+                .unwrap()
+                .and(predicates::str::is_match(r"advanced\s+0/1\s+0s\s+0\s+0\s+0").unwrap()),
+        );
+}
+
+#[test]
+fn run_rustlings_stats_as_csv() {
+    let csv = std::env::temp_dir().join(format!("rustlings_stats_{}.csv", std::process::id()));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["stats", "--csv", csv.to_str().unwrap()])
+        .current_dir("tests/fixture/stats")
+        .assert()
+        .success();
+    let contents = std::fs::read_to_string(&csv).unwrap();
+    std::fs::remove_file(&csv).unwrap();
+    assert!(contents.contains(
+        "finished_exercise,basics,2023-11-14T22:13:20Z,2023-11-14T22:16:40Z,200,3,0,1\n"
+    ));
+    assert!(contents.contains("untouched_exercise,advanced,,,,0,0,0\n"));
+}
+
+#[test]
+fn export_and_report_cohort() {
+    let bundle = std::env::temp_dir().join(format!("rustlings_bundle_{}.json", std::process::id()));
+    let bundle = bundle.to_str().unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args([
+            "export", "--name", "ferris", "--key", "cohort", "-o", bundle,
+        ])
+        .current_dir("tests/fixture/stats")
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["report", bundle, "--key", "cohort"])
+        .current_dir("tests/fixture/stats")
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::is_match(r"ferris\s+1/3 \(33.3 %\)\s+1\s+pending_exercise")
+                .unwrap()
+                .and(predicates::str::contains("Sticking points:")),
+        );
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["report", bundle, "--key", "guess"])
+        .current_dir("tests/fixture/stats")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "the bundle was changed after it was exported",
+        ));
+    std::fs::remove_file(bundle).unwrap();
+}
+
+#[test]
+fn verify_strict_reports_cheating() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--strict"])
+        .current_dir("tests/fixture/strict")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("done_but_failing.rs is marked as done, but doesn't pass")
+                .and(predicates::str::contains(
+                    "removed_tests.rs has 1 of the 2 tests it was shipped with",
+                ))
+                .and(predicates::str::contains(
+                    "done_but_failing.rs can't be checked against its shipped version",
+                ))
+                .and(predicates::str::contains(
+                    "removed_bench.rs has 0 of the 1 tests it was shipped with",
+                ))
+                .and(predicates::str::contains("pending.rs is marked").not())
+                .and(predicates::str::contains("3 of 5 exercises pass.")),
+        );
+}
+
+#[test]
+fn verify_strict_without_rustlings_directory() {
+    let workspace = fresh_workspace("shipped", "strict");
+    assert!(!workspace.join(".rustlings").exists());
+    let verify = || {
+        let mut command = Command::cargo_bin("rustlings").unwrap();
+        command.args(["verify", "--strict"]).current_dir(&workspace);
+        command
+    };
+    verify().assert().success().stdout(
+        predicates::str::contains("can't be checked")
+            .not()
+            .and(predicates::str::contains("0 of 1 exercises pass.")),
+    );
+
+    // Solved by dropping a failing test instead of fixing the code
+    let exercise = workspace.join("exercises/guarded.rs");
+    let source = std::fs::read_to_string(&exercise).unwrap();
+    let (solved, _) = source
+        .split_once("\n    #[test]\n    fn doubles_negative")
+        .unwrap();
+    let solved = solved
+        .replace("// I AM NOT DONE\n", "")
+        .replace("    x\n", "    x + 2\n");
+    std::fs::write(&exercise, format!("{solved}\n}}\n")).unwrap();
+    verify().assert().code(1).stdout(
+        predicates::str::contains("exercises/guarded.rs has 1 of the 2 tests it was shipped with")
+            .and(predicates::str::contains("1 of 1 exercises pass.")),
+    );
+    std::fs::remove_dir_all(workspace).unwrap();
+}
+
+#[test]
+fn run_fails_when_protected_tests_are_altered() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "altered_tests"])
+        .current_dir("tests/fixture/protected")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "The protected code of altered_tests.rs was changed: tests",
+        ));
+}
+
+#[test]
+fn run_allows_reformatted_protected_tests() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "reformatted_tests"])
+        .current_dir("tests/fixture/protected")
+        .assert()
+        .success();
+}
+
+// A copy of the fixture in a temporary directory, for tests that change the workspace
+fn fresh_workspace(fixture: &str, name: &str) -> PathBuf {
+    fn copy(from: &Path, to: &Path) {
+        std::fs::create_dir_all(to).unwrap();
+        for entry in std::fs::read_dir(from).unwrap() {
+            let entry = entry.unwrap();
+            if entry.file_type().unwrap().is_dir() {
+                copy(&entry.path(), &to.join(entry.file_name()));
+            } else {
+                std::fs::copy(entry.path(), to.join(entry.file_name())).unwrap();
+            }
+        }
+    }
+    let workspace = std::env::temp_dir().join(format!("rustlings_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&workspace);
+    copy(&Path::new("tests/fixture").join(fixture), &workspace);
+    workspace
+}
+
+#[test]
+fn run_checks_protected_tests_without_pristine_copies() {
+    let workspace = fresh_workspace("shipped", "protected");
+    let exercise = workspace.join("exercises/guarded.rs");
+    let run = || {
+        let mut command = Command::cargo_bin("rustlings").unwrap();
+        command.args(["run", "guarded"]).current_dir(&workspace);
+        command
+    };
+    run()
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("protected code").not());
+    assert!(workspace
+        .join(".rustlings/pristine/exercises/guarded.rs")
+        .exists());
+
+    let source = std::fs::read_to_string(&exercise).unwrap();
+    std::fs::write(&exercise, source.replace("4);", "2);")).unwrap();
+    run().assert().code(1).stdout(predicates::str::contains(
+        "The protected code of exercises/guarded.rs was changed: tests",
+    ));
+    std::fs::remove_dir_all(workspace).unwrap();
+}
+
+#[test]
+fn pristine_copies_follow_the_shipped_version() {
+    let workspace = fresh_workspace("shipped", "pristine");
+    let info = workspace.join("info.toml");
+    let pristine = workspace.join(".rustlings/pristine/exercises/guarded.rs");
+    let list = || {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .arg("list")
+            .current_dir(&workspace)
+            .assert()
+            .success();
+    };
+    list();
+    assert!(pristine.exists());
+
+    // Another version of the exercise is shipped, like after a `git pull`
+    let shipped = std::fs::read_to_string(&info).unwrap();
+    let digest = shipped.split('"').nth(5).unwrap().to_string();
+    std::fs::write(&info, shipped.replace(&digest, &"0".repeat(64))).unwrap();
+    list();
+    assert!(!pristine.exists());
+
+    std::fs::write(&info, shipped).unwrap();
+    list();
+    assert!(pristine.exists());
+    std::fs::remove_dir_all(workspace).unwrap();
+}
+
+#[test]
+fn run_warns_when_protected_tests_cant_be_checked() {
+    let workspace = fresh_workspace("shipped", "unchecked");
+    let exercise = workspace.join("exercises/guarded.rs");
+    // Changed before rustlings ever ran, so it doesn't match the shipped digest
+    let source = std::fs::read_to_string(&exercise).unwrap();
+    std::fs::write(&exercise, source.replace("4);", "2);")).unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "guarded"])
+        .current_dir(&workspace)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "The protected code of exercises/guarded.rs can't be checked",
+        ));
+    assert!(!workspace.join(".rustlings/pristine").exists());
+    std::fs::remove_dir_all(workspace).unwrap();
+}
+
+#[test]
+fn run_single_test_with_hidden_tests() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--nocapture", "run", "hidden_pass"])
+        .current_dir("tests/fixture/hidden")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "rustlings_hidden_tests::doubles_negative_numbers",
+        ));
+}
+
+#[test]
+fn run_single_test_failing_hidden_tests() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "hidden_fail"])
+        .current_dir("tests/fixture/hidden")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "rustlings_hidden_tests::doubles_negative_numbers",
+        ));
+}
+
+#[test]
+fn run_hidden_tests_of_exercise_with_modules() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["--nocapture", "run", "hidden_module"])
+        .current_dir("tests/fixture/hidden")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "rustlings_hidden_tests::doubles_negative_numbers",
+        ));
+    // The copy with the hidden tests is removed again
+    assert!(std::fs::read_dir("tests/fixture/hidden/modules")
+        .unwrap()
+        .all(|entry| !entry
+            .unwrap()
+            .file_name()
+            .to_string_lossy()
+            .starts_with('.')));
+}
+
+#[test]
+fn run_fails_when_hidden_tests_are_missing() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "hidden_missing"])
+        .current_dir("tests/fixture/hidden")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "The hidden tests hidden/missing.rs weren't found",
+        ))
+        .stderr(predicates::str::contains("panicked").not());
+}
+
+#[test]
+fn mutants_reports_surviving_mutants() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("mutants")
+        .current_dir("tests/fixture/mutants")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("catch all 2 mutants"))
+        .stdout(predicates::str::contains(
+            "solutions/weak.rs:2: age >= 18 => age >= 19",
+        ));
+}
+
+#[test]
+fn mutants_stops_mutants_that_loop_forever() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["mutants", "looping"])
+        .current_dir("tests/fixture/mutants")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("catch all 6 mutants"));
+}
+
+#[test]
+fn mutants_succeeds_when_tests_catch_all_mutants() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["mutants", "strong"])
+        .current_dir("tests/fixture/mutants")
+        .assert()
+        .success();
+}
+
+#[test]
+fn pack_and_init_workspace() {
+    let dir = std::env::temp_dir().join(format!("rustlings_pack_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let archive = dir.join("hidden.tar.gz");
+    let workspace = dir.join("workspace");
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["pack", "-o"])
+        .arg(&archive)
+        .current_dir("tests/fixture/hidden")
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .arg(&archive)
+        .arg(&workspace)
+        .assert()
+        .success();
+    assert!(workspace.join("hidden/hidden_pass.rs").exists());
+    assert!(workspace
+        .join(".rustlings/pristine/hidden_pass.rs")
+        .exists());
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "hidden_pass"])
+        .current_dir(&workspace)
+        .assert()
+        .success();
+
+    // A workspace is only ever set up in an empty directory
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .arg(&archive)
+        .arg(&workspace)
+        .assert()
+        .code(1);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn pack_only_the_shipped_exercise_set() {
+    let workspace = fresh_workspace("shipped", "pack_shipped");
+    let archive = workspace.join("shipped.tar.gz");
+    let unpacked = workspace.join("unpacked");
+    std::fs::write(workspace.join("exercises/notes.txt"), "").unwrap();
+    std::fs::create_dir_all(workspace.join("exercises/target")).unwrap();
+    std::fs::write(workspace.join("exercises/target/build.log"), "").unwrap();
+    let pack = || {
+        let mut command = Command::cargo_bin("rustlings").unwrap();
+        command
+            .args(["pack", "-o"])
+            .arg(&archive)
+            .current_dir(&workspace);
+        command
+    };
+    let unpack = || {
+        let mut command = Command::cargo_bin("rustlings").unwrap();
+        command.arg("unpack").arg(&archive).arg("-o").arg(&unpacked);
+        command
+    };
+
+    pack().assert().success();
+    unpack().assert().success();
+    assert!(unpacked.join("exercises/guarded.rs").exists());
+    assert!(!unpacked.join("exercises/notes.txt").exists());
+    assert!(!unpacked.join("exercises/target").exists());
+
+    // Unpacking again would overwrite the exercises
+    std::fs::write(unpacked.join("exercises/guarded.rs"), "// Mine\n").unwrap();
+    unpack().assert().code(1).stdout(predicates::str::contains(
+        "These files already exist, pass `--force` to overwrite them: info.toml, exercises/guarded.rs",
+    ));
+    assert_eq!(
+        std::fs::read_to_string(unpacked.join("exercises/guarded.rs")).unwrap(),
+        "// Mine\n"
+    );
+    unpack().arg("--force").assert().success();
+    assert_ne!(
+        std::fs::read_to_string(unpacked.join("exercises/guarded.rs")).unwrap(),
+        "// Mine\n"
+    );
+
+    // The packer's own changes to the exercises aren't packed
+    std::fs::write(workspace.join("exercises/guarded.rs"), "// Mine\n").unwrap();
+    pack().assert().code(1).stdout(predicates::str::contains(
+        "exercises/guarded.rs was changed since it was shipped",
+    ));
+    std::fs::remove_dir_all(workspace).unwrap();
+}
+
+#[test]
+fn upgrade_carries_progress_over() {
+    let dir = std::env::temp_dir().join(format!("rustlings_upgrade_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let workspace = dir.join("workspace");
+    for version in ["v1", "v2"] {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["pack", "-o"])
+            .arg(dir.join(format!("{version}.tar.gz")))
+            .current_dir(format!("tests/fixture/upgrade/{version}"))
+            .assert()
+            .success();
+    }
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .arg(dir.join("v1.tar.gz"))
+        .arg(&workspace)
+        .assert()
+        .success();
+    let done = "fn main() {}\n";
+    for name in ["keep", "move", "rewrite"] {
+        std::fs::write(workspace.join(format!("exercises/{name}.rs")), done).unwrap();
+    }
+
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("upgrade")
+        .arg(dir.join("v2.tar.gz"))
+        .current_dir(&workspace)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("move is now called moved"))
+        .stdout(predicates::str::contains(
+            "rewrite has changed since you did it",
+        ))
+        .stdout(predicates::str::contains("fresh is new"))
+        .stdout(predicates::str::contains(
+            "gone isn't part of the exercises",
+        ));
+    let read = |path: &str| std::fs::read_to_string(workspace.join(path)).unwrap();
+    assert_eq!(read("exercises/keep.rs"), done);
+    assert_eq!(read("exercises/moved.rs"), done);
+    assert!(!workspace.join("exercises/move.rs").exists());
+    assert!(read("exercises/rewrite.rs").contains("rewritten"));
+    assert_eq!(read("exercises/rewrite.previous.rs"), done);
+    assert!(read("info.toml").contains("fresh"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn verify_fails_on_too_old_rustc() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/toolchain")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "future.rs needs rustc 99.0.0 or newer",
+        ));
+}
+
+#[test]
+fn run_warns_about_too_old_rustc_up_front() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "pass"])
+        .current_dir("tests/fixture/toolchain")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "1 exercises need rustc 99.0.0 or newer",
+        ));
+}
+
+#[test]
+fn verify_with_missing_toolchain() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--toolchain", "no-such-toolchain"])
+        .current_dir("tests/fixture/toolchain")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "The toolchain no-such-toolchain couldn't be found",
+        ));
+}
+
+#[test]
+fn exercises_compile_with_their_edition() {
+    for name in ["edition2015", "edition2021"] {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["run", name])
+            .current_dir("tests/fixture/edition")
+            .assert()
+            .success();
+    }
+}
+
+#[test]
+fn run_passes_args_and_stdin() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "greet"])
+        .current_dir("tests/fixture/input")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Hello, Ferris!"));
+}
+
+#[test]
+fn run_with_more_input_and_output_than_a_pipe_holds() {
+    let workspace = std::env::temp_dir().join(format!("rustlings_input_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&workspace);
+    std::fs::create_dir_all(&workspace).unwrap();
+    std::fs::copy("tests/fixture/input/echo.rs", workspace.join("echo.rs")).unwrap();
+    let input = "x".repeat(100_000);
+    std::fs::write(
+        workspace.join("info.toml"),
+        format!(
+            "[[exercises]]\nname = \"echo\"\npath = \"echo.rs\"\nmode = \"compile\"\nstdin = \"{input}\"\nhint = \"\"\n"
+        ),
+    )
+    .unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "echo"])
+        .current_dir(&workspace)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Read 100000 bytes"));
+    std::fs::remove_dir_all(workspace).unwrap();
+}
+
+#[test]
+fn run_interactively_reads_the_terminal() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "greet", "--interactive"])
+        .current_dir("tests/fixture/input")
+        .with_stdin()
+        .buffer("Bonjour")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Bonjour, Ferris!"));
+}
+
+#[test]
+fn run_interactively_refuses_tested_exercises() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "testSuccess", "--interactive"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "testSuccess.rs isn't run as a program, so it can't read from the terminal",
+        ));
+}
+
+#[test]
+fn run_bench_reports_timing_relative_to_reference() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "fast"])
+        .current_dir("tests/fixture/bench")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("per iteration"))
+        .stdout(predicates::str::contains("times the reference solution"));
+}
+
+#[test]
+fn run_bench_fails_when_too_slow() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "slow"])
+        .current_dir("tests/fixture/bench")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("too slow"));
+}
+
+#[test]
+fn run_compile_fail_with_expected_error() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "moved"])
+        .current_dir("tests/fixture/compile_fail")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("failed with E0382, as expected"));
+}
+
+#[test]
+fn run_compile_fail_with_other_error() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "mismatched"])
+        .current_dir("tests/fixture/compile_fail")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("but not with E0382"));
+}
+
+#[test]
+fn run_compile_fail_with_any_error() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "any_error"])
+        .current_dir("tests/fixture/compile_fail")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("failed with error, as expected"));
+}
+
+#[test]
+fn run_compile_fail_with_missing_file() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "missing"])
+        .current_dir("tests/fixture/compile_fail")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("couldn't read"));
+}
+
+#[test]
+fn run_compile_fail_that_compiles() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compiles"])
+        .current_dir("tests/fixture/compile_fail")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("has to reject it with E0382"));
+}
+
+// Whether the command succeeds, to skip the tests needing tools that aren't installed
+fn succeeds(program: &str, args: &[&str]) -> bool {
+    Command::new(program)
+        .args(args)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[test]
+fn run_unsafe_without_undefined_behavior() {
+    if !succeeds("rustc", &["+nightly", "--version"]) {
+        return;
+    }
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sound"])
+        .env("RUSTUP_TOOLCHAIN", "nightly")
+        .current_dir("tests/fixture/unsafe")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_unsafe_reports_undefined_behavior() {
+    if !succeeds("rustc", &["+nightly", "--version"]) {
+        return;
+    }
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "unsound"])
+        .env("RUSTUP_TOOLCHAIN", "nightly")
+        .current_dir("tests/fixture/unsafe")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("has undefined behavior"));
+}
+
+#[test]
+fn run_unsafe_without_checker_explains_setup() {
+    if !succeeds("rustc", &["+stable", "--version"]) {
+        return;
+    }
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sound"])
+        .env("RUSTUP_TOOLCHAIN", "stable")
+        .current_dir("tests/fixture/unsafe")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "rustup +nightly component add miri",
+        ));
+}
+
+#[test]
+fn run_unsafe_under_miri() {
+    if !succeeds("cargo", &["miri", "--version"]) {
+        return;
+    }
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "sound"])
+        .current_dir("tests/fixture/unsafe")
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "unsound"])
+        .current_dir("tests/fixture/unsafe")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Undefined Behavior"));
+}
+
+#[test]
+fn run_doctest_reports_each_example() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "documented"])
+        .current_dir("tests/fixture/doctest")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("add (line 3)"));
+}
+
+#[test]
+fn run_doctest_with_failing_example() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "misdocumented"])
+        .env("RUSTLINGS_PLAIN", "1")
+        .current_dir("tests/fixture/doctest")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("✗ sub (line 12)"));
+}
+
+#[test]
+fn verify_doctests_in_order() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/doctest")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "Testing of misdocumented.rs failed",
+        ));
+}
+
+#[test]
+fn run_property_test() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "reverse"])
+        .current_dir("tests/fixture/property")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_property_test_shrinks_failing_input() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "clamp"])
+        .env("RUSTLINGS_SEED", "42")
+        .current_dir("tests/fixture/property")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("doesn't hold for 1001"))
+        .stdout(predicates::str::contains("RUSTLINGS_SEED=42"));
+}
+
+#[test]
+fn watch_topic_subset() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "basics"])
+        .current_dir("tests/fixture/watch")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "All exercises matching basics completed!",
+        ));
+}
+
+#[test]
+fn watch_exercise_by_exact_name() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "finished"])
+        .current_dir("tests/fixture/watch")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "All exercises matching finished completed!",
+        ));
+}
+
+#[test]
+fn watch_without_matching_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["watch", "nothing"])
+        .current_dir("tests/fixture/watch")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("No exercise matches nothing."));
+}
+
+#[test]
+fn list_filter_by_topic() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names", "--filter", "advanced"])
+        .current_dir("tests/fixture/watch")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("pending"))
+        .stdout(predicates::str::contains("finished\n").not());
+}
+
+#[test]
+fn review_in_scratch_copy() {
+    let workspace = std::env::temp_dir().join(format!("rustlings_review_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&workspace);
+    let write = |path: &str, contents: &str| {
+        let path = workspace.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    };
+    write(
+        "info.toml",
+        "[[exercises]]\nname = \"add\"\npath = \"exercises/add.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    );
+    let answer = "fn main() {\n    let x: i32 = 1;\n    println!(\"{x}\");\n}\n";
+    write("exercises/add.rs", answer);
+    write(
+        ".rustlings/pristine/exercises/add.rs",
+        "// I AM NOT DONE\nfn main() {\n    let x: i32 = \"1\";\n    println!(\"{x}\");\n}\n",
+    );
+    // Due since the epoch
+    write(
+        ".rustlings/review.json",
+        r#"{"cards": {"add": {"due": 0, "interval": 4, "ease": 2.5, "reviews": 0, "failures": 0}}}"#,
+    );
+    let review = || {
+        let mut command = Command::cargo_bin("rustlings").unwrap();
+        command.arg("review").current_dir(&workspace);
+        command
+    };
+    let scratch = workspace.join(".rustlings/review/exercises/add.rs");
+
+    review()
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Time to review exercises/add.rs"));
+    assert!(std::fs::read_to_string(&scratch)
+        .unwrap()
+        .contains("I AM NOT DONE"));
+    review()
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("doesn't pass yet"));
+
+    std::fs::write(&scratch, answer).unwrap();
+    review()
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Reviewed exercises/add.rs!"));
+    assert!(!scratch.exists());
+    assert_eq!(
+        std::fs::read_to_string(workspace.join("exercises/add.rs")).unwrap(),
+        answer
+    );
+    review()
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Nothing to review right now"));
+    review()
+        .arg("--list")
+        .assert()
+        .success()
+        .stdout(predicates::str::is_match(r"add\s+\d{4}-\d{2}-\d{2}\s+1").unwrap());
+    std::fs::remove_dir_all(workspace).unwrap();
+}
+
+#[test]
+fn review_without_rustlings_directory() {
+    let workspace = fresh_workspace("shipped", "review_fresh");
+    let rustlings = |arg: &str| {
+        let mut command = Command::cargo_bin("rustlings").unwrap();
+        command.arg(arg).current_dir(&workspace);
+        command
+    };
+    // The learner starts with the exercise as shipped
+    rustlings("list").assert().success();
+
+    let exercise = workspace.join("exercises/guarded.rs");
+    let source = std::fs::read_to_string(&exercise).unwrap();
+    let solved = source
+        .replace("// I AM NOT DONE\n", "")
+        .replace("    x\n", "    x * 2\n");
+    std::fs::write(&exercise, solved).unwrap();
+    // Due since the epoch
+    std::fs::write(
+        workspace.join(".rustlings/review.json"),
+        r#"{"cards": {"guarded": {"due": 0, "interval": 4, "ease": 2.5, "reviews": 0, "failures": 0}}}"#,
+    )
+    .unwrap();
+    rustlings("review")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Time to review exercises/guarded.rs",
+        ));
+    assert_eq!(
+        std::fs::read_to_string(workspace.join(".rustlings/review/exercises/guarded.rs")).unwrap(),
+        source
+    );
+    std::fs::remove_dir_all(workspace).unwrap();
+}
+
+#[test]
+fn generate_variants_from_seed() {
+    let dir = std::env::temp_dir().join(format!("rustlings_variant_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let generate = |workspace: &str, seed: &str| {
+        let workspace = dir.join(workspace);
+        std::fs::create_dir_all(workspace.join("exercises")).unwrap();
+        for file in ["info.toml", "exercises/limit.rs.tmpl"] {
+            std::fs::copy(
+                Path::new("tests/fixture/variant").join(file),
+                workspace.join(file),
+            )
+            .unwrap();
+        }
+        std::fs::create_dir_all(workspace.join(".rustlings")).unwrap();
+        std::fs::write(workspace.join(".rustlings/seed"), seed).unwrap();
+        // Only `generate` creates the variants
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["run", "limit"])
+            .current_dir(&workspace)
+            .assert()
+            .code(1)
+            .stdout(predicates::str::contains(
+                "limit haven't been generated from their templates yet",
+            ));
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .arg("generate")
+            .current_dir(&workspace)
+            .assert()
+            .success();
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["run", "limit"])
+            .current_dir(&workspace)
+            .assert()
+            .success();
+        let source = std::fs::read_to_string(workspace.join("exercises/limit.rs")).unwrap();
+        let pristine =
+            std::fs::read_to_string(workspace.join(".rustlings/pristine/exercises/limit.rs"))
+                .unwrap();
+        assert_eq!(source, pristine);
+        assert!(!source.contains("{{"));
+        source
+    };
+    let first = generate("first", "7");
+    assert_eq!(generate("second", "7"), first);
+    assert_ne!(generate("third", "8"), first);
+    std::fs::remove_dir_all(dir).unwrap();
+}